@@ -0,0 +1,37 @@
+//! Escape throughput: four sequential `replace` passes versus the
+//! single-pass scanners.
+//!
+//! Run with `cargo bench --bench escape`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use namespace::{escape, escape_fast, unescape, unescape_fast};
+
+/// A large RDFa-flavoured document with a realistic density of
+/// characters needing escaping.
+fn large_document() -> String {
+    let fragment = "<div rel=\"eRDFa:embedded\" prefix=\"a&b\">x &amp; y</div>\n";
+    fragment.repeat(4096)
+}
+
+fn bench_escape(c: &mut Criterion) {
+    let document = large_document();
+    c.bench_function("escape/sequential", |b| {
+        b.iter(|| escape(black_box(&document)))
+    });
+    c.bench_function("escape/single-pass", |b| {
+        b.iter(|| escape_fast(black_box(&document)))
+    });
+}
+
+fn bench_unescape(c: &mut Criterion) {
+    let escaped = escape(&large_document());
+    c.bench_function("unescape/sequential", |b| {
+        b.iter(|| unescape(black_box(&escaped)))
+    });
+    c.bench_function("unescape/single-pass", |b| {
+        b.iter(|| unescape_fast(black_box(&escaped)))
+    });
+}
+
+criterion_group!(benches, bench_escape, bench_unescape);
+criterion_main!(benches);
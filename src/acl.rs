@@ -0,0 +1,682 @@
+//! Layered access control over nested-encrypted eRDFa payloads.
+//!
+//! A document is wrapped in encryption layers matching an ACL chain:
+//! the outermost (public) layer is readable by anyone, each deeper
+//! layer requires a threshold of keys for its ACL entry.
+
+use std::io::{self, Write};
+
+use crate::crypto::simple_hash;
+
+/// Sensitivity of one ACL layer, from least to most restricted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AccessLevel {
+    Public,
+    Authenticated,
+    Confidential,
+    Secret,
+    TopSecret,
+}
+
+/// Errors from structural ACL operations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AclError {
+    /// No layer, or no unique root layer, exists.
+    MissingRoot,
+    MultipleRoots,
+    /// A parent link points at a missing or out-of-range layer.
+    BrokenChain { layer: usize },
+    /// Following parent links from this layer never reaches the root.
+    CycleDetected { layer: usize },
+    /// A layer would be less sensitive than the layer it nests inside.
+    LevelRegression {
+        parent: AccessLevel,
+        child: AccessLevel,
+    },
+}
+
+/// One layer of the ACL chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ACLEntry {
+    pub level: AccessLevel,
+    pub required_keys: Vec<Vec<u8>>,
+    pub threshold: usize,
+    pub encryption_key: Vec<u8>,
+    pub parent_layer: Option<usize>,
+    /// Keys explicitly barred from this layer. Presenting any of them
+    /// denies access outright, threshold notwithstanding — the hook for
+    /// revoked members whose keys may still sit in `required_keys`.
+    pub denied_keys: Vec<Vec<u8>>,
+}
+
+/// A chain of ACL layers rooted at a public layer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayeredACL {
+    pub layers: Vec<ACLEntry>,
+}
+
+impl LayeredACL {
+    /// A fresh ACL containing only the public root layer.
+    pub fn new() -> Self {
+        LayeredACL {
+            layers: vec![ACLEntry {
+                level: AccessLevel::Public,
+                required_keys: Vec::new(),
+                threshold: 0,
+                encryption_key: Vec::new(),
+                parent_layer: None,
+                denied_keys: Vec::new(),
+            }],
+        }
+    }
+
+    /// Append a layer beneath the current deepest one. Sensitivity must
+    /// not regress: a layer nested inside a `Secret` one cannot be
+    /// `Public`, or peeling encryption toward the inside would reveal
+    /// less-protected data behind more-protected data.
+    pub fn add_layer(
+        &mut self,
+        level: AccessLevel,
+        required_keys: Vec<Vec<u8>>,
+        threshold: usize,
+        encryption_key: Vec<u8>,
+    ) -> Result<(), AclError> {
+        if let Some(parent) = self.layers.last() {
+            if level < parent.level {
+                return Err(AclError::LevelRegression {
+                    parent: parent.level,
+                    child: level,
+                });
+            }
+        }
+        let parent = self.layers.len().checked_sub(1);
+        self.layers.push(ACLEntry {
+            level,
+            required_keys,
+            threshold,
+            encryption_key,
+            parent_layer: parent,
+            denied_keys: Vec::new(),
+        });
+        Ok(())
+    }
+
+    /// True if `keys` satisfies the threshold of the given layer.
+    /// Counted over distinct required keys, so presenting the same
+    /// valid key twice cannot stand in for two keys. Any presented key
+    /// on the layer's deny list refuses access outright, even when the
+    /// remaining keys would meet the threshold on their own.
+    pub fn can_access(&self, layer: usize, keys: &[Vec<u8>]) -> bool {
+        match self.layers.get(layer) {
+            Some(entry) => {
+                if keys.iter().any(|key| entry.denied_keys.contains(key)) {
+                    return false;
+                }
+                let matching = entry
+                    .required_keys
+                    .iter()
+                    .filter(|required| keys.contains(required))
+                    .count();
+                matching >= entry.threshold
+            }
+            None => false,
+        }
+    }
+
+    /// Put `key` on a layer's deny list, e.g. when revoking a member
+    /// whose key cannot be rotated out of `required_keys` yet.
+    pub fn deny_key(&mut self, layer: usize, key: Vec<u8>) -> Result<(), AclError> {
+        match self.layers.get_mut(layer) {
+            Some(entry) => {
+                if !entry.denied_keys.contains(&key) {
+                    entry.denied_keys.push(key);
+                }
+                Ok(())
+            }
+            None => Err(AclError::BrokenChain { layer }),
+        }
+    }
+
+    /// Remove a layer, revoking its access tier: children are
+    /// re-pointed at the revoked layer's parent and later indices
+    /// shift down. The public root cannot be revoked.
+    pub fn revoke_layer(&mut self, layer: usize) -> Result<(), AclError> {
+        if layer >= self.layers.len() {
+            return Err(AclError::BrokenChain { layer });
+        }
+        if layer == 0 {
+            return Err(AclError::MissingRoot);
+        }
+        let grandparent = self.layers[layer].parent_layer;
+        self.layers.remove(layer);
+        for entry in &mut self.layers {
+            entry.parent_layer = match entry.parent_layer {
+                Some(parent) if parent == layer => grandparent,
+                Some(parent) if parent > layer => Some(parent - 1),
+                other => other,
+            };
+        }
+        Ok(())
+    }
+
+    /// Replace a layer's encryption key, returning the old one so
+    /// existing ciphertext can be rewrapped via
+    /// [`NestedEncryption::reencrypt_layer`].
+    pub fn rotate_key(&mut self, layer: usize, new_key: Vec<u8>) -> Result<Vec<u8>, AclError> {
+        match self.layers.get_mut(layer) {
+            Some(entry) => Ok(std::mem::replace(&mut entry.encryption_key, new_key)),
+            None => Err(AclError::BrokenChain { layer }),
+        }
+    }
+
+    /// Confirm the parent links form a single acyclic chain with
+    /// exactly one root (the public layer). Called after structural
+    /// edits such as layer insertion or removal.
+    pub fn validate_structure(&self) -> Result<(), AclError> {
+        let mut roots = self
+            .layers
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.parent_layer.is_none())
+            .map(|(i, _)| i);
+        match (roots.next(), roots.next()) {
+            (None, _) => return Err(AclError::MissingRoot),
+            (Some(_), Some(_)) => return Err(AclError::MultipleRoots),
+            (Some(_), None) => {}
+        }
+        // Each non-root layer must reach the root within len steps.
+        for start in 0..self.layers.len() {
+            let mut current = start;
+            for _ in 0..self.layers.len() {
+                match self.layers[current].parent_layer {
+                    None => break,
+                    Some(parent) if parent >= self.layers.len() => {
+                        return Err(AclError::BrokenChain { layer: current });
+                    }
+                    Some(parent) => current = parent,
+                }
+            }
+            if self.layers[current].parent_layer.is_some() {
+                return Err(AclError::CycleDetected { layer: start });
+            }
+        }
+        // A chain has at most one child per layer.
+        let mut child_count = vec![0usize; self.layers.len()];
+        for entry in &self.layers {
+            if let Some(parent) = entry.parent_layer {
+                child_count[parent] += 1;
+            }
+        }
+        if let Some(layer) = child_count.iter().position(|&c| c > 1) {
+            return Err(AclError::BrokenChain { layer });
+        }
+        Ok(())
+    }
+}
+
+impl Default for LayeredACL {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Repeating-key XOR; applying it twice with the same key is identity.
+fn xor_cipher(data: &[u8], key: &[u8]) -> Vec<u8> {
+    if key.is_empty() {
+        return data.to_vec();
+    }
+    data.iter()
+        .enumerate()
+        .map(|(i, &byte)| byte ^ key[i % key.len()])
+        .collect()
+}
+
+/// The payload encrypted once per non-public ACL layer; `layers[0]` is
+/// the fully wrapped ciphertext.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NestedEncryption {
+    pub layers: Vec<Vec<u8>>,
+}
+
+impl NestedEncryption {
+    /// Encrypt `data` once per non-public ACL layer.
+    ///
+    /// Invariant: `layers[i]` is the ciphertext visible to a holder of
+    /// keys up through layer `i` — the payload still wrapped by every
+    /// deeper layer's key. The fully peeled plaintext is never stored;
+    /// it only exists as the return value of
+    /// [`decrypt_to_layer`](Self::decrypt_to_layer) at the innermost
+    /// layer.
+    pub fn encrypt_nested(data: &[u8], acl: &LayeredACL) -> Self {
+        let mut layers = Vec::new();
+        let mut current = data.to_vec();
+        for entry in acl.layers.iter().rev() {
+            if entry.level != AccessLevel::Public {
+                current = xor_cipher(&current, &entry.encryption_key);
+                layers.push(current.clone());
+            }
+        }
+        layers.reverse();
+        debug_assert!(
+            data.is_empty()
+                || acl.layers.iter().all(|entry| entry.level == AccessLevel::Public)
+                || layers.iter().all(|layer| layer.as_slice() != data),
+            "a stored layer leaks the raw plaintext; is an encryption key empty?"
+        );
+        NestedEncryption { layers }
+    }
+
+    /// Rewrap the ciphertext after rotating layer `layer`'s key: every
+    /// stored view still wrapped by that key (indices below `layer`)
+    /// is unwrapped with `old_key` and wrapped with `new_key`.
+    pub fn reencrypt_layer(&mut self, layer: usize, old_key: &[u8], new_key: &[u8]) {
+        for view in self.layers.iter_mut().take(layer) {
+            let unwrapped = xor_cipher(view, old_key);
+            *view = xor_cipher(&unwrapped, new_key);
+        }
+    }
+
+    /// Peel encryption down to `target_layer`.
+    ///
+    /// `keys` are the actual per-layer encryption keys, indexed like
+    /// `LayeredACL::layers` — `keys[i]` is layer `i`'s
+    /// `encryption_key`, with the public root's entry ignored. Access
+    /// keys only gate who may call this (see
+    /// [`LayeredACL::can_access`]); they play no part in the cipher.
+    /// With the correct keys this reverses
+    /// [`encrypt_nested`](Self::encrypt_nested) exactly, so the
+    /// innermost layer yields the original plaintext.
+    pub fn decrypt_to_layer(&self, target_layer: usize, keys: &[Vec<u8>]) -> Option<Vec<u8>> {
+        let mut current = self.layers.first()?.clone();
+        for layer in 1..=target_layer {
+            let key = keys.get(layer)?;
+            current = xor_cipher(&current, key);
+        }
+        Some(current)
+    }
+
+    /// Streaming [`decrypt_to_layer`](Self::decrypt_to_layer): the
+    /// peeled bytes are written straight into `out` in fixed-size
+    /// chunks, so no intermediate per-layer buffer is materialized.
+    /// Returns the number of plaintext bytes written; a missing layer
+    /// or key surfaces as [`io::ErrorKind::InvalidInput`].
+    pub fn decrypt_to_layer_into<W: Write>(
+        &self,
+        target_layer: usize,
+        keys: &[Vec<u8>],
+        out: &mut W,
+    ) -> io::Result<usize> {
+        let invalid = |what: &str| io::Error::new(io::ErrorKind::InvalidInput, what.to_string());
+        let source = self.layers.first().ok_or_else(|| invalid("no layers"))?;
+        let mut applied: Vec<&[u8]> = Vec::with_capacity(target_layer);
+        for layer in 1..=target_layer {
+            let key = keys.get(layer).ok_or_else(|| invalid("missing layer key"))?;
+            // An empty key is the identity cipher; skip it.
+            if !key.is_empty() {
+                applied.push(key);
+            }
+        }
+        let mut buffer = [0u8; 8192];
+        for (chunk_start, chunk) in source.chunks(buffer.len()).enumerate() {
+            let offset = chunk_start * buffer.len();
+            for (i, &byte) in chunk.iter().enumerate() {
+                let mut plain = byte;
+                for key in &applied {
+                    plain ^= key[(offset + i) % key.len()];
+                }
+                buffer[i] = plain;
+            }
+            out.write_all(&buffer[..chunk.len()])?;
+        }
+        Ok(source.len())
+    }
+}
+
+/// One recorded attempt to access a layer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessAttempt {
+    pub caller: Vec<u8>,
+    pub layer: usize,
+    pub granted: bool,
+    pub timestamp: u64,
+}
+
+/// Append-only audit log of access attempts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccessLog {
+    attempts: Vec<AccessAttempt>,
+}
+
+impl AccessLog {
+    fn record(&mut self, attempt: AccessAttempt) {
+        self.attempts.push(attempt);
+    }
+
+    pub fn attempts(&self) -> &[AccessAttempt] {
+        &self.attempts
+    }
+}
+
+/// A transaction whose payload is gated by a layered ACL.
+#[derive(Debug, Clone)]
+pub struct LayeredSemanticTransaction {
+    pub acl: LayeredACL,
+    pub nested_layers: NestedEncryption,
+    pub layer_hashes: Vec<[u8; 32]>,
+    /// Audit log of access attempts; `None` (the default) records
+    /// nothing, keeping the logging overhead opt-in.
+    pub access_log: Option<AccessLog>,
+}
+
+impl LayeredSemanticTransaction {
+    pub fn new(data: &[u8], acl: LayeredACL) -> Self {
+        let nested_layers = NestedEncryption::encrypt_nested(data, &acl);
+        let layer_hashes = nested_layers
+            .layers
+            .iter()
+            .map(|layer| simple_hash(layer))
+            .collect();
+        LayeredSemanticTransaction {
+            acl,
+            nested_layers,
+            layer_hashes,
+            access_log: None,
+        }
+    }
+
+    /// Enable the audit log; subsequent [`access_layer`]
+    /// (Self::access_layer) calls are recorded.
+    pub fn with_access_log(mut self) -> Self {
+        self.access_log = Some(AccessLog::default());
+        self
+    }
+
+    /// Every recorded access attempt, oldest first; empty when logging
+    /// is disabled.
+    pub fn access_history(&self) -> &[AccessAttempt] {
+        self.access_log
+            .as_ref()
+            .map(AccessLog::attempts)
+            .unwrap_or(&[])
+    }
+
+    /// Rotate one layer's encryption key: the ACL entry, the stored
+    /// ciphertext and the layer hashes all move to the new key, so
+    /// [`access_layer`](Self::access_layer) keeps working.
+    pub fn rotate_layer_key(&mut self, layer: usize, new_key: Vec<u8>) -> Result<(), AclError> {
+        let old_key = self.acl.rotate_key(layer, new_key.clone())?;
+        self.nested_layers.reencrypt_layer(layer, &old_key, &new_key);
+        self.layer_hashes = self
+            .nested_layers
+            .layers
+            .iter()
+            .map(|layer| simple_hash(layer))
+            .collect();
+        Ok(())
+    }
+
+    /// Check one encrypted layer against its recorded hash.
+    pub fn verify_layer(&self, layer: usize) -> bool {
+        match (self.nested_layers.layers.get(layer), self.layer_hashes.get(layer)) {
+            (Some(bytes), Some(hash)) => simple_hash(bytes) == *hash,
+            _ => false,
+        }
+    }
+
+    /// Check every stored layer against its recorded hash, stopping at
+    /// the first mismatch.
+    pub fn verify_all(&self) -> bool {
+        self.layer_hashes.len() == self.nested_layers.layers.len()
+            && (0..self.layer_hashes.len()).all(|layer| self.verify_layer(layer))
+    }
+
+    /// Decrypt down to `layer` if the whole stack verifies (see
+    /// [`verify_all`](Self::verify_all)) and the caller's access `keys`
+    /// satisfy its ACL entry, recording the attempt and its outcome
+    /// when logging is enabled. The decryption keys come from the ACL
+    /// entries themselves; the caller's keys only prove the right to
+    /// use them.
+    pub fn access_layer(
+        &mut self,
+        caller: &[u8],
+        layer: usize,
+        keys: &[Vec<u8>],
+        timestamp: u64,
+    ) -> Option<Vec<u8>> {
+        let result = if self.verify_all() && self.acl.can_access(layer, keys) {
+            let layer_keys: Vec<Vec<u8>> = self
+                .acl
+                .layers
+                .iter()
+                .map(|entry| entry.encryption_key.clone())
+                .collect();
+            self.nested_layers.decrypt_to_layer(layer, &layer_keys)
+        } else {
+            None
+        };
+        if let Some(log) = &mut self.access_log {
+            log.record(AccessAttempt {
+                caller: caller.to_vec(),
+                layer,
+                granted: result.is_some(),
+                timestamp,
+            });
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub(crate) fn two_layer_acl() -> LayeredACL {
+        let mut acl = LayeredACL::new();
+        acl.add_layer(
+            AccessLevel::Authenticated,
+            vec![b"key-a".to_vec(), b"key-b".to_vec()],
+            1,
+            b"auth-secret".to_vec(),
+        )
+        .expect("sensitivity increases");
+        acl.add_layer(
+            AccessLevel::Secret,
+            vec![b"key-c".to_vec()],
+            1,
+            b"top-secret".to_vec(),
+        )
+        .expect("sensitivity increases");
+        acl
+    }
+
+    #[test]
+    fn test_add_layer_rejects_sensitivity_regression() {
+        let mut acl = two_layer_acl();
+        // Equal sensitivity is allowed; a regression is not.
+        assert_eq!(
+            acl.add_layer(AccessLevel::Secret, Vec::new(), 0, b"k".to_vec()),
+            Ok(())
+        );
+        assert_eq!(
+            acl.add_layer(AccessLevel::Public, Vec::new(), 0, b"k".to_vec()),
+            Err(AclError::LevelRegression {
+                parent: AccessLevel::Secret,
+                child: AccessLevel::Public,
+            })
+        );
+        assert_eq!(acl.layers.len(), 4);
+    }
+
+    #[test]
+    fn test_can_access_threshold() {
+        let acl = two_layer_acl();
+        assert!(acl.can_access(1, &[b"key-a".to_vec()]));
+        assert!(!acl.can_access(1, &[b"key-z".to_vec()]));
+    }
+
+    #[test]
+    fn test_can_access_ignores_duplicate_keys() {
+        let mut acl = LayeredACL::new();
+        acl.add_layer(
+            AccessLevel::Secret,
+            vec![b"key-1".to_vec(), b"key-2".to_vec(), b"key-3".to_vec()],
+            2,
+            b"secret".to_vec(),
+        )
+        .expect("sensitivity increases");
+        // One valid key presented twice is still one key.
+        assert!(!acl.can_access(1, &[b"key-1".to_vec(), b"key-1".to_vec()]));
+        assert!(acl.can_access(1, &[b"key-1".to_vec(), b"key-2".to_vec()]));
+    }
+
+    #[test]
+    fn test_denied_key_blocks_access_despite_threshold() {
+        let mut acl = two_layer_acl();
+        // key-a is both required and revoked: denial wins.
+        acl.deny_key(1, b"key-a".to_vec()).expect("layer exists");
+        assert!(!acl.can_access(1, &[b"key-a".to_vec()]));
+        // Even alongside another key that meets the threshold alone.
+        assert!(!acl.can_access(1, &[b"key-a".to_vec(), b"key-b".to_vec()]));
+        // A clean presentation is unaffected.
+        assert!(acl.can_access(1, &[b"key-b".to_vec()]));
+        // Deny lists are per layer.
+        assert!(acl.can_access(2, &[b"key-c".to_vec()]));
+        assert_eq!(
+            acl.deny_key(9, b"key-a".to_vec()),
+            Err(AclError::BrokenChain { layer: 9 })
+        );
+    }
+
+    #[test]
+    fn test_access_log_records_each_outcome() {
+        let mut tx =
+            LayeredSemanticTransaction::new(b"payload", two_layer_acl()).with_access_log();
+        assert!(tx.access_layer(b"alice", 1, &[b"key-a".to_vec()], 100).is_some());
+        assert!(tx.access_layer(b"mallory", 1, &[b"key-z".to_vec()], 101).is_none());
+        let history = tx.access_history();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].caller, b"alice");
+        assert!(history[0].granted);
+        assert_eq!(history[1].caller, b"mallory");
+        assert!(!history[1].granted);
+        assert_eq!(history[1].timestamp, 101);
+        // Without opting in, nothing is recorded.
+        let mut silent = LayeredSemanticTransaction::new(b"payload", two_layer_acl());
+        silent.access_layer(b"alice", 1, &[b"key-a".to_vec()], 100);
+        assert!(silent.access_history().is_empty());
+    }
+
+    #[test]
+    fn test_streaming_decrypt_matches_buffered_decrypt() {
+        let payload: Vec<u8> = (0..100_000u32).map(|i| (i * 31 % 251) as u8).collect();
+        let acl = two_layer_acl();
+        let nested = NestedEncryption::encrypt_nested(&payload, &acl);
+        let keys: Vec<Vec<u8>> = acl.layers.iter().map(|e| e.encryption_key.clone()).collect();
+        let buffered = nested.decrypt_to_layer(2, &keys).expect("keys present");
+        assert_eq!(buffered, payload);
+        let mut sink = Vec::new();
+        let written = nested
+            .decrypt_to_layer_into(2, &keys, &mut sink)
+            .expect("sink writes succeed");
+        assert_eq!(written, payload.len());
+        assert_eq!(sink, buffered);
+        // A missing key is an input error, not a partial write.
+        let mut sink = Vec::new();
+        assert!(nested.decrypt_to_layer_into(2, &keys[..2], &mut sink).is_err());
+        assert!(sink.is_empty());
+    }
+
+    #[test]
+    fn test_revoke_middle_layer_repoints_children() {
+        let mut acl = two_layer_acl();
+        acl.revoke_layer(1).expect("middle layer revocable");
+        assert_eq!(acl.layers.len(), 2);
+        // The old Secret layer now hangs off the root directly.
+        assert_eq!(acl.layers[1].level, AccessLevel::Secret);
+        assert_eq!(acl.layers[1].parent_layer, Some(0));
+        assert_eq!(acl.validate_structure(), Ok(()));
+        // The root itself cannot be revoked.
+        assert_eq!(acl.revoke_layer(0), Err(AclError::MissingRoot));
+        assert_eq!(acl.revoke_layer(9), Err(AclError::BrokenChain { layer: 9 }));
+    }
+
+    #[test]
+    fn test_rotate_key_then_decrypt_with_new_key() {
+        let mut tx = LayeredSemanticTransaction::new(b"rotated payload", two_layer_acl());
+        tx.rotate_layer_key(2, b"fresh-secret".to_vec())
+            .expect("layer exists");
+        assert!(tx.verify_all());
+        assert_eq!(tx.acl.layers[2].encryption_key, b"fresh-secret");
+        let recovered = tx.access_layer(b"carol", 2, &[b"key-c".to_vec()], 1);
+        assert_eq!(recovered.as_deref(), Some(b"rotated payload".as_slice()));
+    }
+
+    #[test]
+    fn test_corrupted_layer_fails_verification_and_access() {
+        let mut tx = LayeredSemanticTransaction::new(b"payload", two_layer_acl());
+        assert!(tx.verify_all());
+        tx.nested_layers.layers[1][0] ^= 0xFF;
+        assert!(!tx.verify_layer(1));
+        assert!(!tx.verify_all());
+        // The untouched layer still verifies on its own.
+        assert!(tx.verify_layer(0));
+        // Access is refused outright on a corrupted stack.
+        assert!(tx.access_layer(b"alice", 1, &[b"key-a".to_vec()], 1).is_none());
+    }
+
+    #[test]
+    fn test_no_stored_layer_equals_plaintext() {
+        let tx = LayeredSemanticTransaction::new(b"the secret payload", two_layer_acl());
+        // Two non-public ACL layers store two ciphertext views.
+        assert_eq!(tx.nested_layers.layers.len(), 2);
+        for layer in &tx.nested_layers.layers {
+            assert_ne!(layer.as_slice(), b"the secret payload");
+        }
+        // A single non-public layer used to store the plaintext as its
+        // innermost entry; it must not any more.
+        let mut acl = LayeredACL::new();
+        acl.add_layer(AccessLevel::Secret, vec![b"key-c".to_vec()], 1, b"k".to_vec())
+            .expect("sensitivity increases");
+        let single = LayeredSemanticTransaction::new(b"the secret payload", acl);
+        assert_eq!(single.nested_layers.layers.len(), 1);
+        assert_ne!(single.nested_layers.layers[0].as_slice(), b"the secret payload");
+    }
+
+    #[test]
+    fn test_access_layer_recovers_innermost_plaintext() {
+        let mut tx = LayeredSemanticTransaction::new(b"the secret payload", two_layer_acl());
+        let recovered = tx.access_layer(b"carol", 2, &[b"key-c".to_vec()], 1);
+        assert_eq!(recovered.as_deref(), Some(b"the secret payload".as_slice()));
+        // The intermediate layer is still ciphertext under its own key.
+        let partial = tx.access_layer(b"alice", 1, &[b"key-a".to_vec()], 2).unwrap();
+        assert_ne!(partial, b"the secret payload".to_vec());
+        // Access keys that fail the ACL never reach the cipher.
+        assert!(tx.access_layer(b"mallory", 2, &[b"key-a".to_vec()], 3).is_none());
+    }
+
+    #[test]
+    fn test_validate_structure_accepts_chain() {
+        let acl = two_layer_acl();
+        assert_eq!(acl.validate_structure(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_structure_rejects_corrupted_parent() {
+        let mut acl = two_layer_acl();
+        // Point the deepest layer back at itself: a cycle.
+        acl.layers[2].parent_layer = Some(2);
+        assert_eq!(
+            acl.validate_structure(),
+            Err(AclError::CycleDetected { layer: 2 })
+        );
+        // An out-of-range parent is a broken chain.
+        acl.layers[2].parent_layer = Some(9);
+        assert_eq!(
+            acl.validate_structure(),
+            Err(AclError::BrokenChain { layer: 2 })
+        );
+        // A second root is rejected.
+        acl.layers[2].parent_layer = None;
+        assert_eq!(acl.validate_structure(), Err(AclError::MultipleRoots));
+    }
+}
@@ -0,0 +1,1042 @@
+//! A semantic blockchain storing eRDFa payloads.
+//!
+//! Transactions carry raw RDFa alongside an extraction witness proving
+//! the data was recovered from a stego channel. Miners bundle
+//! transactions into blocks chained by header hashes.
+
+use std::collections::HashSet;
+
+use crate::crypto::{simple_hash, ExtractionWitness};
+
+/// One RDFa payload submitted for inclusion in the chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SemanticTransaction {
+    pub rdfa_data: Vec<u8>,
+    pub fee: u64,
+    pub timestamp: u64,
+    pub signature: Vec<u8>,
+    pub witness: ExtractionWitness,
+    /// Ids of transactions that must already be mined before this one
+    /// may be included in a block (e.g. a claim referencing a prior
+    /// subject). Empty for independent transactions.
+    pub depends_on: Vec<[u8; 32]>,
+}
+
+/// Hash used for duplicate detection, over the fields a resubmitted
+/// transaction cannot vary without becoming a genuinely different
+/// submission: payload, timestamp, and signature.
+pub fn tx_hash(tx: &SemanticTransaction) -> [u8; 32] {
+    let mut bytes = tx.rdfa_data.clone();
+    bytes.extend_from_slice(&tx.timestamp.to_be_bytes());
+    bytes.extend_from_slice(&tx.signature);
+    simple_hash(&bytes)
+}
+
+/// Stable transaction identifier used for canonical ordering.
+fn tx_id(tx: &SemanticTransaction) -> [u8; 32] {
+    let mut bytes = tx.rdfa_data.clone();
+    bytes.extend_from_slice(&tx.fee.to_be_bytes());
+    bytes.extend_from_slice(&tx.timestamp.to_be_bytes());
+    bytes.extend_from_slice(&tx.signature);
+    simple_hash(&bytes)
+}
+
+impl SemanticTransaction {
+    /// The transaction's stable identifier, as committed by the block's
+    /// Merkle root.
+    pub fn id(&self) -> [u8; 32] {
+        tx_id(self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockHeader {
+    pub previous_hash: [u8; 32],
+    pub merkle_root: [u8; 32],
+    pub timestamp: u64,
+    pub nonce: u64,
+    pub difficulty: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SemanticBlock {
+    pub header: BlockHeader,
+    /// Hash of the mined header; meets the header's difficulty target.
+    pub block_hash: [u8; 32],
+    pub transactions: Vec<SemanticTransaction>,
+    pub reward: u64,
+    pub miner_address: Vec<u8>,
+}
+
+/// Rolling FNV-fed hash. Cheaper than `simple_hash` and good enough for
+/// the Merkle tree and proof-of-work, which only need every input byte
+/// to influence every output lane and input order to matter.
+fn rolling_hash(bytes: &[u8]) -> [u8; 32] {
+    let mut state = 0xcbf2_9ce4_8422_2325u64;
+    for &byte in bytes {
+        state = (state ^ byte as u64).wrapping_mul(0x100_0000_01b3);
+    }
+    let mut hash = [0u8; 32];
+    for lane in &mut hash {
+        state ^= state >> 33;
+        state = state.wrapping_mul(0xff51_afd7_ed55_8ccd);
+        *lane = (state >> 24) as u8;
+    }
+    hash
+}
+
+/// Hash a block header, nonce included, so it can chain blocks and
+/// carry the proof-of-work.
+pub fn hash_header(header: &BlockHeader) -> [u8; 32] {
+    let mut bytes = header.previous_hash.to_vec();
+    bytes.extend_from_slice(&header.merkle_root);
+    bytes.extend_from_slice(&header.timestamp.to_be_bytes());
+    bytes.extend_from_slice(&header.nonce.to_be_bytes());
+    bytes.extend_from_slice(&header.difficulty.to_be_bytes());
+    rolling_hash(&bytes)
+}
+
+fn merkle_parent(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut bytes = left.to_vec();
+    bytes.extend_from_slice(right);
+    rolling_hash(&bytes)
+}
+
+/// All levels of the binary Merkle tree, leaves first, with the last
+/// node duplicated on odd counts so every node has a sibling.
+fn merkle_levels(leaves: Vec<[u8; 32]>) -> Vec<Vec<[u8; 32]>> {
+    let mut levels = Vec::new();
+    let mut level = leaves;
+    loop {
+        if level.len() > 1 && level.len() % 2 == 1 {
+            level.push(*level.last().expect("level is non-empty"));
+        }
+        levels.push(level.clone());
+        if level.len() <= 1 {
+            break;
+        }
+        level = level
+            .chunks(2)
+            .map(|pair| merkle_parent(&pair[0], &pair[1]))
+            .collect();
+    }
+    levels
+}
+
+/// Fold a leaf and its sibling path back up to a root. `tx_index` is
+/// the leaf's position in the block, which determines on which side
+/// each sibling hashes in.
+pub fn verify_merkle_proof(
+    leaf: &[u8; 32],
+    tx_index: usize,
+    proof: &[[u8; 32]],
+    merkle_root: &[u8; 32],
+) -> bool {
+    let mut acc = *leaf;
+    let mut index = tx_index;
+    for sibling in proof {
+        acc = if index % 2 == 1 {
+            merkle_parent(sibling, &acc)
+        } else {
+            merkle_parent(&acc, sibling)
+        };
+        index /= 2;
+    }
+    acc == *merkle_root
+}
+
+/// Whether `hash` has at least `difficulty` leading zero bits.
+pub fn meets_difficulty(hash: &[u8; 32], difficulty: u32) -> bool {
+    let mut bits = 0;
+    for &byte in hash {
+        if byte == 0 {
+            bits += 8;
+        } else {
+            bits += byte.leading_zeros();
+            break;
+        }
+    }
+    bits >= difficulty
+}
+
+impl SemanticBlock {
+    /// Sibling path proving the transaction at `tx_index` is committed
+    /// by this block's Merkle root; `None` if the index is out of
+    /// range. Proof size is logarithmic in the block.
+    pub fn merkle_proof(&self, tx_index: usize) -> Option<Vec<[u8; 32]>> {
+        if tx_index >= self.transactions.len() {
+            return None;
+        }
+        let leaves: Vec<[u8; 32]> = self.transactions.iter().map(tx_id).collect();
+        let levels = merkle_levels(leaves);
+        let mut proof = Vec::new();
+        let mut index = tx_index;
+        for level in &levels[..levels.len() - 1] {
+            proof.push(level[index ^ 1]);
+            index /= 2;
+        }
+        Some(proof)
+    }
+}
+
+/// Semantic constraints over a transaction's parsed triples, checked in
+/// addition to the structural checks in
+/// [`SemanticBlockchain::validate_transaction`]. Implementations might
+/// require certain predicates or restrict object values.
+pub trait RdfaSchema {
+    fn validate(&self, triples: &[(String, String, String)]) -> bool;
+}
+
+/// Fee policy: a base fee plus a per-byte storage fee.
+pub struct FeeSchedule {
+    pub base_fee: u64,
+    pub per_byte: u64,
+}
+
+impl FeeSchedule {
+    pub fn calculate_fee(&self, tx: &SemanticTransaction) -> u64 {
+        self.base_fee + self.per_byte * tx.rdfa_data.len() as u64
+    }
+
+    /// Mining priority of a transaction: fee paid per payload byte.
+    /// An empty payload counts as one byte so the fee still ranks it.
+    pub fn priority(&self, tx: &SemanticTransaction) -> u64 {
+        tx.fee / (tx.rdfa_data.len().max(1) as u64)
+    }
+}
+
+impl Default for FeeSchedule {
+    fn default() -> Self {
+        FeeSchedule {
+            base_fee: 1,
+            per_byte: 1,
+        }
+    }
+}
+
+/// Breakdown of what a miner earns for a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinerReward {
+    pub block_reward: u64,
+    pub storage_bonus: u64,
+    pub verification_bonus: u64,
+}
+
+impl MinerReward {
+    /// Base subsidy per block, before fees and bonuses.
+    pub const BLOCK_SUBSIDY: u64 = 50;
+    /// Payload bytes stored per unit of storage bonus.
+    pub const STORAGE_BONUS_DIVISOR: u64 = 10;
+    /// Bonus per transaction carrying a valid extraction witness.
+    pub const WITNESS_BONUS: u64 = 5;
+
+    /// What mining `transactions` into a block earns: the subsidy plus
+    /// collected fees, a bonus proportional to the RDFa bytes stored,
+    /// and a bonus per verified witness.
+    pub fn for_block(transactions: &[SemanticTransaction]) -> Self {
+        let total_fees: u64 = transactions.iter().map(|tx| tx.fee).sum();
+        let stored_bytes: u64 = transactions.iter().map(|tx| tx.rdfa_data.len() as u64).sum();
+        let verified = transactions
+            .iter()
+            .filter(|tx| tx.witness.verify(&tx.rdfa_data))
+            .count() as u64;
+        MinerReward {
+            block_reward: Self::BLOCK_SUBSIDY + total_fees,
+            storage_bonus: stored_bytes / Self::STORAGE_BONUS_DIVISOR,
+            verification_bonus: verified * Self::WITNESS_BONUS,
+        }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.block_reward + self.storage_bonus + self.verification_bonus
+    }
+}
+
+/// Target time between blocks, in the chain's timestamp units, used to
+/// retarget difficulty.
+pub const TARGET_BLOCK_INTERVAL: u64 = 10;
+
+/// Fingerprint of the canonical genesis header: the SHA-256 digest of
+/// its serialized fields, every one zero except `difficulty = 1` (see
+/// [`SemanticBlockchain::genesis_block`]).
+pub const GENESIS_HASH: [u8; 32] = [
+    0x51, 0x42, 0xb7, 0x78, 0xa0, 0xd0, 0x16, 0x97, 0xa1, 0xec, 0x89, 0xef, 0x46, 0x59, 0x41,
+    0x63, 0x57, 0x99, 0x2f, 0xca, 0x41, 0x46, 0x52, 0x79, 0xff, 0x06, 0x55, 0x61, 0x8f, 0x75,
+    0x67, 0x0c,
+];
+
+/// The chain, its mempool, and the fee policy.
+pub struct SemanticBlockchain {
+    pub chain: Vec<SemanticBlock>,
+    pub mempool: Vec<SemanticTransaction>,
+    pub fee_schedule: FeeSchedule,
+    /// Most transactions one block may hold; lower-priority overflow
+    /// stays in the mempool for a later block.
+    pub max_block_txs: usize,
+}
+
+impl SemanticBlockchain {
+    pub fn new() -> Self {
+        SemanticBlockchain {
+            chain: vec![Self::genesis_block()],
+            mempool: Vec::new(),
+            fee_schedule: FeeSchedule::default(),
+            max_block_txs: 1024,
+        }
+    }
+
+    fn genesis_block() -> SemanticBlock {
+        let header = BlockHeader {
+            previous_hash: [0u8; 32],
+            merkle_root: [0u8; 32],
+            timestamp: 0,
+            nonce: 0,
+            difficulty: 1,
+        };
+        SemanticBlock {
+            block_hash: hash_header(&header),
+            header,
+            transactions: Vec::new(),
+            reward: 0,
+            miner_address: Vec::new(),
+        }
+    }
+
+    fn header_fingerprint(header: &BlockHeader) -> [u8; 32] {
+        let mut bytes = header.previous_hash.to_vec();
+        bytes.extend_from_slice(&header.merkle_root);
+        bytes.extend_from_slice(&header.timestamp.to_be_bytes());
+        bytes.extend_from_slice(&header.nonce.to_be_bytes());
+        bytes.extend_from_slice(&header.difficulty.to_be_bytes());
+        simple_hash(&bytes)
+    }
+
+    /// Whether this chain starts from the canonical genesis block.
+    pub fn verify_genesis(&self) -> bool {
+        match self.chain.first() {
+            Some(genesis) => {
+                genesis.transactions.is_empty()
+                    && Self::header_fingerprint(&genesis.header) == GENESIS_HASH
+            }
+            None => false,
+        }
+    }
+
+    /// Adopt `candidate` as the new chain if it starts from our genesis
+    /// and is strictly longer than what we have. Two nodes with
+    /// different genesis blocks must never "agree", however long the
+    /// impostor chain is.
+    pub fn try_replace(&mut self, candidate: Vec<SemanticBlock>) -> bool {
+        let valid_genesis = candidate
+            .first()
+            .map(|genesis| {
+                genesis.transactions.is_empty()
+                    && Self::header_fingerprint(&genesis.header) == GENESIS_HASH
+            })
+            .unwrap_or(false);
+        if !valid_genesis || candidate.len() <= self.chain.len() {
+            return false;
+        }
+        self.chain = candidate;
+        true
+    }
+
+    pub fn validate_transaction(
+        &self,
+        tx: &SemanticTransaction,
+        schema: Option<&dyn RdfaSchema>,
+    ) -> bool {
+        if !tx.witness.verify(&tx.rdfa_data) || tx.fee < self.fee_schedule.calculate_fee(tx) {
+            return false;
+        }
+        match schema {
+            Some(schema) => {
+                let triples = Self::parse_triples(&String::from_utf8_lossy(&tx.rdfa_data));
+                schema.validate(&triples)
+            }
+            None => true,
+        }
+    }
+
+    /// Whether an identical transaction already sits in the mempool or
+    /// was mined into a block.
+    fn is_duplicate(&self, tx: &SemanticTransaction) -> bool {
+        let hash = tx_hash(tx);
+        self.mempool.iter().any(|pending| tx_hash(pending) == hash)
+            || self
+                .chain
+                .iter()
+                .flat_map(|block| &block.transactions)
+                .any(|mined| tx_hash(mined) == hash)
+    }
+
+    /// Add a transaction to the mempool if it validates and is not a
+    /// duplicate of a pending or mined transaction.
+    pub fn add_transaction(&mut self, tx: SemanticTransaction) -> bool {
+        self.add_transaction_with_schema(tx, None)
+    }
+
+    /// Like [`add_transaction`](Self::add_transaction), additionally
+    /// requiring the payload's triples to conform to `schema`.
+    pub fn add_transaction_with_schema(
+        &mut self,
+        tx: SemanticTransaction,
+        schema: Option<&dyn RdfaSchema>,
+    ) -> bool {
+        if !self.validate_transaction(&tx, schema) || self.is_duplicate(&tx) {
+            return false;
+        }
+        self.mempool.push(tx);
+        true
+    }
+
+    fn get_last_block_hash(&self) -> [u8; 32] {
+        self.chain
+            .last()
+            .map(|block| hash_header(&block.header))
+            .unwrap_or([0u8; 32])
+    }
+
+    /// Walk the chain verifying every block's `previous_hash` links to
+    /// the full header hash of its predecessor — nonce and timestamp
+    /// included — and that each stored `block_hash` matches its header.
+    pub fn validate_chain(&self) -> bool {
+        let links_hold = self
+            .chain
+            .windows(2)
+            .all(|pair| pair[1].header.previous_hash == hash_header(&pair[0].header));
+        links_hold
+            && self
+                .chain
+                .iter()
+                .all(|block| block.block_hash == hash_header(&block.header))
+    }
+
+    /// Root of the binary Merkle tree over transaction ids. An empty
+    /// block keeps the all-zero root of the genesis convention.
+    fn calculate_merkle_root(transactions: &[SemanticTransaction]) -> [u8; 32] {
+        if transactions.is_empty() {
+            return [0u8; 32];
+        }
+        let leaves: Vec<[u8; 32]> = transactions.iter().map(tx_id).collect();
+        *merkle_levels(leaves)
+            .last()
+            .and_then(|level| level.first())
+            .expect("tree of a non-empty block has a root")
+    }
+
+    /// The difficulty for a block mined at `timestamp`: one bit harder
+    /// when blocks arrive faster than [`TARGET_BLOCK_INTERVAL`], one
+    /// bit easier (never below 1) when they lag at more than twice it.
+    fn next_difficulty(&self, timestamp: u64) -> u32 {
+        let last = self.chain.last().expect("genesis always present");
+        let elapsed = timestamp.saturating_sub(last.header.timestamp);
+        if elapsed < TARGET_BLOCK_INTERVAL {
+            last.header.difficulty + 1
+        } else if elapsed > TARGET_BLOCK_INTERVAL * 2 {
+            (last.header.difficulty.saturating_sub(1)).max(1)
+        } else {
+            last.header.difficulty
+        }
+    }
+
+    /// Mine the mempool into a new block.
+    ///
+    /// Transactions whose `depends_on` ids are not all already mined
+    /// stay in the mempool and wait for a later block; dependencies are
+    /// never satisfied by transactions in the block being built.
+    /// When the remainder exceeds `max_block_txs`, the highest-priority
+    /// transactions (fee per byte, see [`FeeSchedule::priority`]) are
+    /// taken and the rest wait for a later block. The included set is
+    /// then laid out in canonical order — fee (tip) descending, then
+    /// transaction id ascending — so two miners with the same mempool
+    /// build byte-identical blocks regardless of the order transactions
+    /// arrived in. The nonce is incremented until the header hash meets
+    /// the retargeted difficulty.
+    pub fn mine_block(&mut self, miner_address: Vec<u8>, timestamp: u64) -> &SemanticBlock {
+        let mined: HashSet<[u8; 32]> = self
+            .chain
+            .iter()
+            .flat_map(|block| block.transactions.iter().map(tx_id))
+            .collect();
+        let (mut transactions, deferred): (Vec<SemanticTransaction>, Vec<SemanticTransaction>) =
+            self.mempool
+                .drain(..)
+                .partition(|tx| tx.depends_on.iter().all(|dep| mined.contains(dep)));
+        self.mempool = deferred;
+        if transactions.len() > self.max_block_txs {
+            transactions.sort_by(|a, b| {
+                self.fee_schedule
+                    .priority(b)
+                    .cmp(&self.fee_schedule.priority(a))
+                    .then_with(|| tx_id(a).cmp(&tx_id(b)))
+            });
+            let overflow = transactions.split_off(self.max_block_txs);
+            self.mempool.extend(overflow);
+        }
+        transactions.sort_by(|a, b| b.fee.cmp(&a.fee).then_with(|| tx_id(a).cmp(&tx_id(b))));
+        let reward = MinerReward::for_block(&transactions);
+        let difficulty = self.next_difficulty(timestamp);
+        let mut header = BlockHeader {
+            previous_hash: self.get_last_block_hash(),
+            merkle_root: Self::calculate_merkle_root(&transactions),
+            timestamp,
+            nonce: 0,
+            difficulty,
+        };
+        let mut block_hash = hash_header(&header);
+        while !meets_difficulty(&block_hash, difficulty) {
+            header.nonce += 1;
+            block_hash = hash_header(&header);
+        }
+        let block = SemanticBlock {
+            header,
+            block_hash,
+            transactions,
+            reward: reward.total(),
+            miner_address,
+        };
+        self.chain.push(block);
+        self.chain.last().expect("block just pushed")
+    }
+
+    /// Build an inclusion proof for the mined transaction with id
+    /// `tx_id`: its index within the block plus the Merkle sibling
+    /// path. `None` if no block contains it.
+    pub fn generate_inclusion_proof(&self, target: &[u8; 32]) -> Option<(usize, Vec<[u8; 32]>)> {
+        for block in &self.chain {
+            if let Some(index) = block.transactions.iter().position(|tx| tx_id(tx) == *target) {
+                return Some((index, block.merkle_proof(index)?));
+            }
+        }
+        None
+    }
+
+    /// Verify that `tx_id` at `tx_index` is committed by `merkle_root`
+    /// given an inclusion proof, without the block body. Standalone so
+    /// light clients can verify against a header obtained elsewhere.
+    pub fn verify_inclusion(
+        tx_id: &[u8; 32],
+        tx_index: usize,
+        proof: &[[u8; 32]],
+        merkle_root: &[u8; 32],
+    ) -> bool {
+        verify_merkle_proof(tx_id, tx_index, proof, merkle_root)
+    }
+
+    pub fn get_block_count(&self) -> usize {
+        self.chain.len()
+    }
+
+    /// The block at `height`, with genesis at height 0.
+    pub fn get_block(&self, height: usize) -> Option<&SemanticBlock> {
+        self.chain.get(height)
+    }
+
+    /// Height of the chain tip; genesis sits at height 0.
+    pub fn height(&self) -> u64 {
+        self.chain.len() as u64 - 1
+    }
+
+    /// How many blocks sit on top of `block_index`, counting the block
+    /// itself: the tip has 1 confirmation, blocks past the tip have 0.
+    pub fn confirmations(&self, block_index: usize) -> usize {
+        self.chain.len().saturating_sub(block_index)
+    }
+
+    pub fn is_confirmed(&self, block_index: usize, min_confirmations: usize) -> bool {
+        self.confirmations(block_index) >= min_confirmations
+    }
+
+    pub fn get_block_by_hash(&self, block_hash: &[u8; 32]) -> Option<&SemanticBlock> {
+        self.chain.iter().find(|block| block.block_hash == *block_hash)
+    }
+
+    /// Total rewards accrued by `miner_address` across the chain.
+    /// Genesis is skipped; nobody mined it.
+    pub fn balance_of(&self, miner_address: &[u8]) -> u64 {
+        self.chain
+            .iter()
+            .skip(1)
+            .filter(|block| block.miner_address == miner_address)
+            .map(|block| block.reward)
+            .sum()
+    }
+
+    pub fn get_transaction_count(&self) -> usize {
+        self.chain.iter().map(|block| block.transactions.len()).sum()
+    }
+
+    /// Value of the `name="..."` attribute inside an opening tag.
+    fn attribute_value(tag: &str, name: &str) -> Option<String> {
+        let marker = format!("{}=\"", name);
+        let start = tag.find(&marker)? + marker.len();
+        let end = tag[start..].find('"')? + start;
+        Some(tag[start..end].to_string())
+    }
+
+    /// Parse `(subject, predicate, object)` triples out of one RDFa
+    /// payload: every tag with a `property` attribute yields a triple,
+    /// with `about` as the subject (empty if absent) and the element's
+    /// immediate text as the object.
+    fn parse_triples(rdfa: &str) -> Vec<(String, String, String)> {
+        let mut triples = Vec::new();
+        let mut rest = rdfa;
+        while let Some(open) = rest.find('<') {
+            let after = &rest[open + 1..];
+            let close = match after.find('>') {
+                Some(c) => c,
+                None => break,
+            };
+            let tag = &after[..close];
+            let body = &after[close + 1..];
+            if let Some(predicate) = Self::attribute_value(tag, "property") {
+                let subject = Self::attribute_value(tag, "about").unwrap_or_default();
+                let text_end = body.find('<').unwrap_or(body.len());
+                triples.push((subject, predicate, body[..text_end].trim().to_string()));
+            }
+            rest = body;
+        }
+        triples
+    }
+
+    /// Parsed triples whose predicate equals `predicate` exactly, so a
+    /// query for `foaf:name` no longer matches payloads that merely
+    /// mention the string somewhere.
+    pub fn query_triples(&self, predicate: &str) -> Vec<(String, String, String)> {
+        self.chain
+            .iter()
+            .flat_map(|block| &block.transactions)
+            .flat_map(|tx| Self::parse_triples(&String::from_utf8_lossy(&tx.rdfa_data)))
+            .filter(|(_, p, _)| p == predicate)
+            .collect()
+    }
+
+    /// One-call summary of the chain for dashboards and monitoring.
+    pub fn stats(&self) -> ChainStats {
+        let predicates: HashSet<String> = self
+            .chain
+            .iter()
+            .flat_map(|block| &block.transactions)
+            .flat_map(|tx| Self::parse_triples(&String::from_utf8_lossy(&tx.rdfa_data)))
+            .map(|(_, predicate, _)| predicate)
+            .collect();
+        let miners: HashSet<&[u8]> = self
+            .chain
+            .iter()
+            .skip(1)
+            .map(|block| block.miner_address.as_slice())
+            .collect();
+        let average_block_time = if self.chain.len() < 2 {
+            0.0
+        } else {
+            let first = self.chain.first().expect("genesis always present");
+            let last = self.chain.last().expect("genesis always present");
+            (last.header.timestamp - first.header.timestamp) as f64
+                / (self.chain.len() - 1) as f64
+        };
+        ChainStats {
+            block_count: self.chain.len(),
+            total_transactions: self.get_transaction_count(),
+            total_fees: self
+                .chain
+                .iter()
+                .flat_map(|block| &block.transactions)
+                .map(|tx| tx.fee)
+                .sum(),
+            distinct_predicates: predicates.len(),
+            distinct_miners: miners.len(),
+            average_block_time,
+        }
+    }
+
+    /// Return the raw RDFa payloads mentioning `predicate`.
+    pub fn query_rdfa(&self, predicate: &str) -> Vec<&Vec<u8>> {
+        self.chain
+            .iter()
+            .flat_map(|block| &block.transactions)
+            .filter(|tx| {
+                String::from_utf8_lossy(&tx.rdfa_data).contains(predicate)
+            })
+            .map(|tx| &tx.rdfa_data)
+            .collect()
+    }
+}
+
+impl Default for SemanticBlockchain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Aggregated chain statistics, from [`SemanticBlockchain::stats`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainStats {
+    /// Blocks in the chain, genesis included.
+    pub block_count: usize,
+    /// Mined transactions across all blocks.
+    pub total_transactions: usize,
+    /// Sum of fees across all mined transactions.
+    pub total_fees: u64,
+    /// Distinct predicates across all mined triples.
+    pub distinct_predicates: usize,
+    /// Distinct miner addresses, genesis excluded.
+    pub distinct_miners: usize,
+    /// Mean timestamp spacing between consecutive blocks; 0.0 while
+    /// only genesis exists.
+    pub average_block_time: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    pub(crate) fn make_tx(rdfa: &str, fee: u64, timestamp: u64) -> SemanticTransaction {
+        let rdfa_data = rdfa.as_bytes().to_vec();
+        let witness = ExtractionWitness::generate(&rdfa_data, vec![0]);
+        SemanticTransaction {
+            rdfa_data,
+            fee,
+            timestamp,
+            signature: vec![7],
+            witness,
+            depends_on: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_add_and_mine_transaction() {
+        let mut chain = SemanticBlockchain::new();
+        assert!(chain.add_transaction(make_tx("<div property=\"rss:title\">t</div>", 100, 1)));
+        let block = chain.mine_block(b"miner".to_vec(), 10);
+        assert_eq!(block.transactions.len(), 1);
+        assert_eq!(chain.get_block_count(), 2);
+        assert!(chain.mempool.is_empty());
+    }
+
+    #[test]
+    fn test_stats_summarize_small_chain() {
+        let mut chain = SemanticBlockchain::new();
+        assert!(chain.add_transaction(make_tx("<div property=\"rss:title\">t</div>", 100, 1)));
+        assert!(chain.add_transaction(make_tx("<div property=\"rss:link\">l</div>", 120, 2)));
+        chain.mine_block(b"alice".to_vec(), 10);
+        // A repeated predicate must not inflate the distinct count.
+        assert!(chain.add_transaction(make_tx("<div property=\"rss:title\">t2</div>", 90, 3)));
+        chain.mine_block(b"bob".to_vec(), 30);
+        let stats = chain.stats();
+        assert_eq!(stats.block_count, 3);
+        assert_eq!(stats.total_transactions, 3);
+        assert_eq!(stats.total_fees, 310);
+        assert_eq!(stats.distinct_predicates, 2);
+        assert_eq!(stats.distinct_miners, 2);
+        // Genesis at 0, tips at 10 and 30: two intervals averaging 15.
+        assert_eq!(stats.average_block_time, 15.0);
+    }
+
+    #[test]
+    fn test_dependent_transaction_waits_for_dependency() {
+        let mut chain = SemanticBlockchain::new();
+        let parent = make_tx("<div about=\"#s\" property=\"rss:title\">t</div>", 100, 1);
+        let mut child = make_tx("<div about=\"#s\" property=\"rss:link\">l</div>", 100, 2);
+        child.depends_on = vec![parent.id()];
+        assert!(chain.add_transaction(parent));
+        assert!(chain.add_transaction(child.clone()));
+        // The dependency is in the same mempool, not yet in the chain,
+        // so the child sits out the first block.
+        let block = chain.mine_block(b"miner".to_vec(), 10);
+        assert_eq!(block.transactions.len(), 1);
+        assert_eq!(chain.mempool.len(), 1);
+        // Once the parent is mined the child is eligible.
+        let block = chain.mine_block(b"miner".to_vec(), 30);
+        assert_eq!(block.transactions.len(), 1);
+        assert_eq!(block.transactions[0].id(), child.id());
+        assert!(chain.mempool.is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_transaction_rejected() {
+        let mut chain = SemanticBlockchain::new();
+        let tx = make_tx("<div property=\"rss:title\">t</div>", 100, 1);
+        assert!(chain.add_transaction(tx.clone()));
+        // Identical resubmission is refused while pending...
+        assert!(!chain.add_transaction(tx.clone()));
+        chain.mine_block(b"miner".to_vec(), 10);
+        // ...and after it has been mined.
+        assert!(!chain.add_transaction(tx));
+    }
+
+    struct RequiredPredicate(&'static str);
+
+    impl RdfaSchema for RequiredPredicate {
+        fn validate(&self, triples: &[(String, String, String)]) -> bool {
+            triples.iter().any(|(_, p, _)| p == self.0)
+        }
+    }
+
+    #[test]
+    fn test_height_and_confirmation_depth() {
+        let mut chain = SemanticBlockchain::new();
+        for i in 0..4u64 {
+            assert!(chain.add_transaction(make_tx(
+                &format!("<div property=\"p{}\">v</div>", i),
+                100,
+                i,
+            )));
+            chain.mine_block(b"miner".to_vec(), (i + 1) * 10);
+        }
+        assert_eq!(chain.get_block_count(), 5);
+        assert_eq!(chain.height(), 4);
+        // The tip counts itself as its only confirmation.
+        assert_eq!(chain.confirmations(4), 1);
+        assert_eq!(chain.confirmations(1), 4);
+        assert_eq!(chain.confirmations(9), 0);
+        assert!(chain.is_confirmed(1, 3));
+        assert!(!chain.is_confirmed(4, 2));
+    }
+
+    #[test]
+    fn test_schema_validation_requires_predicate() {
+        let mut chain = SemanticBlockchain::new();
+        let schema = RequiredPredicate("foaf:name");
+        assert!(chain.add_transaction_with_schema(
+            make_tx("<div about=\"#a\" property=\"foaf:name\">Alice</div>", 100, 1),
+            Some(&schema),
+        ));
+        // Structurally valid but missing the required predicate.
+        assert!(!chain.add_transaction_with_schema(
+            make_tx("<div about=\"#b\" property=\"dc:title\">x</div>", 100, 2),
+            Some(&schema),
+        ));
+        assert_eq!(chain.mempool.len(), 1);
+    }
+
+    #[test]
+    fn test_block_lookup_and_miner_balance() {
+        let mut chain = SemanticBlockchain::new();
+        assert!(chain.add_transaction(make_tx("<div property=\"a\">1</div>", 100, 1)));
+        let first = chain.mine_block(b"miner".to_vec(), 10).clone();
+        assert!(chain.add_transaction(make_tx("<div property=\"b\">2</div>", 100, 2)));
+        let second = chain.mine_block(b"miner".to_vec(), 20).clone();
+        chain.mine_block(b"other".to_vec(), 30);
+        assert_eq!(
+            chain.get_block(1).map(|b| b.block_hash),
+            Some(first.block_hash)
+        );
+        assert!(chain.get_block(9).is_none());
+        assert_eq!(
+            chain.get_block_by_hash(&second.block_hash).map(|b| b.header.timestamp),
+            Some(20)
+        );
+        assert!(chain.get_block_by_hash(&[0xAB; 32]).is_none());
+        assert_eq!(chain.balance_of(b"miner"), first.reward + second.reward);
+        assert_eq!(chain.balance_of(b"nobody"), 0);
+    }
+
+    #[test]
+    fn test_block_reward_includes_storage_and_witness_bonuses() {
+        let mut chain = SemanticBlockchain::new();
+        let big = "x".repeat(200);
+        let mut total_fees = 0;
+        for i in 0..3 {
+            let tx = make_tx(&format!("<div property=\"p{}\">{}</div>", i, big), 300, i);
+            total_fees += tx.fee;
+            assert!(chain.add_transaction(tx));
+        }
+        let block = chain.mine_block(b"miner".to_vec(), 10);
+        let flat = MinerReward::BLOCK_SUBSIDY + total_fees;
+        assert!(block.reward > flat, "{} <= {}", block.reward, flat);
+        let stored: u64 = block.transactions.iter().map(|tx| tx.rdfa_data.len() as u64).sum();
+        assert_eq!(
+            block.reward,
+            flat + stored / MinerReward::STORAGE_BONUS_DIVISOR + 3 * MinerReward::WITNESS_BONUS
+        );
+    }
+
+    #[test]
+    fn test_low_priority_transactions_deferred_past_full_block() {
+        let mut chain = SemanticBlockchain::new();
+        chain.max_block_txs = 2;
+        let cheap = make_tx("<div property=\"a\">low tip</div>", 40, 1);
+        assert!(chain.add_transaction(cheap.clone()));
+        assert!(chain.add_transaction(make_tx("<div property=\"b\">2</div>", 200, 2)));
+        assert!(chain.add_transaction(make_tx("<div property=\"c\">3</div>", 300, 3)));
+        let block = chain.mine_block(b"miner".to_vec(), 10).clone();
+        // Only the two best fee-per-byte transactions fit; the cheap
+        // one waits in the mempool for the next block.
+        assert_eq!(block.transactions.len(), 2);
+        assert!(block.transactions.iter().all(|tx| tx.fee >= 200));
+        assert_eq!(chain.mempool, vec![cheap.clone()]);
+        let next = chain.mine_block(b"miner".to_vec(), 20);
+        assert_eq!(next.transactions, vec![cheap]);
+        assert!(chain.mempool.is_empty());
+    }
+
+    #[test]
+    fn test_block_overflow_keeps_dependency_deferred_transactions_pending() {
+        let mut chain = SemanticBlockchain::new();
+        chain.max_block_txs = 2;
+        let parent = make_tx("<div property=\"a\">parent</div>", 300, 1);
+        let mut child = make_tx("<div property=\"b\">child</div>", 300, 2);
+        child.depends_on = vec![parent.id()];
+        let cheap = make_tx("<div property=\"c\">low tip</div>", 40, 3);
+        assert!(chain.add_transaction(parent.clone()));
+        assert!(chain.add_transaction(child.clone()));
+        assert!(chain.add_transaction(cheap.clone()));
+        assert!(chain.add_transaction(make_tx("<div property=\"d\">4</div>", 200, 4)));
+        // The child waits for its dependency, the cheap transaction
+        // overflows the block limit; both must still be pending.
+        let block = chain.mine_block(b"miner".to_vec(), 10).clone();
+        assert_eq!(block.transactions.len(), 2);
+        assert_eq!(chain.mempool.len(), 2);
+        // The next block picks both of them up.
+        let next = chain.mine_block(b"miner".to_vec(), 20);
+        assert_eq!(next.transactions.len(), 2);
+        assert!(chain.mempool.is_empty());
+    }
+
+    #[test]
+    fn test_query_triples_matches_exact_predicate_only() {
+        let mut chain = SemanticBlockchain::new();
+        assert!(chain.add_transaction(make_tx(
+            "<div about=\"#alice\" property=\"foaf:name\">Alice</div>",
+            100,
+            1,
+        )));
+        // Mentions foaf:name in its object but uses another predicate.
+        assert!(chain.add_transaction(make_tx(
+            "<div about=\"#bob\" property=\"dc:title\">foaf:name primer</div>",
+            100,
+            2,
+        )));
+        chain.mine_block(b"miner".to_vec(), 10);
+        let triples = chain.query_triples("foaf:name");
+        assert_eq!(
+            triples,
+            vec![("#alice".into(), "foaf:name".into(), "Alice".into())]
+        );
+        // The substring query still matches both payloads.
+        assert_eq!(chain.query_rdfa("foaf:name").len(), 2);
+    }
+
+    #[test]
+    fn test_underfunded_transaction_rejected() {
+        let mut chain = SemanticBlockchain::new();
+        assert!(!chain.add_transaction(make_tx("<div>long enough content</div>", 0, 1)));
+    }
+
+    #[test]
+    fn test_validate_chain_detects_interior_tampering() {
+        let mut chain = SemanticBlockchain::new();
+        assert!(chain.add_transaction(make_tx("<div property=\"a\">1</div>", 100, 1)));
+        chain.mine_block(b"miner".to_vec(), 10);
+        assert!(chain.add_transaction(make_tx("<div property=\"b\">2</div>", 100, 2)));
+        chain.mine_block(b"miner".to_vec(), 20);
+        assert!(chain.validate_chain());
+        // Nudging an interior timestamp breaks the link to block 2.
+        chain.chain[1].header.timestamp += 1;
+        assert!(!chain.validate_chain());
+    }
+
+    #[test]
+    fn test_mining_meets_difficulty_target() {
+        let mut chain = SemanticBlockchain::new();
+        assert!(chain.add_transaction(make_tx("<div property=\"rss:title\">t</div>", 100, 1)));
+        // Five units after genesis is faster than the target interval,
+        // so difficulty retargets from 1 to 2.
+        let block = chain.mine_block(b"miner".to_vec(), 5).clone();
+        assert_eq!(block.header.difficulty, 2);
+        assert_eq!(block.block_hash, hash_header(&block.header));
+        assert!(meets_difficulty(&block.block_hash, 2));
+        // A slow block drops the difficulty back down.
+        let slow = chain.mine_block(b"miner".to_vec(), 5 + TARGET_BLOCK_INTERVAL * 3);
+        assert_eq!(slow.header.difficulty, 1);
+    }
+
+    #[test]
+    fn test_genesis_verification_rejects_mismatched_chain() {
+        let mut chain = SemanticBlockchain::new();
+        assert!(chain.verify_genesis());
+        // A longer chain built on a different genesis must be refused.
+        let mut impostor_genesis = SemanticBlockchain::genesis_block();
+        impostor_genesis.header.timestamp = 1;
+        let impostor = vec![impostor_genesis.clone(), impostor_genesis];
+        assert!(!chain.try_replace(impostor));
+        assert_eq!(chain.get_block_count(), 1);
+        // A longer chain on the canonical genesis is adopted.
+        let mut longer = SemanticBlockchain::new();
+        longer.mine_block(b"miner".to_vec(), 10);
+        assert!(chain.try_replace(longer.chain.clone()));
+        assert_eq!(chain.get_block_count(), 2);
+    }
+
+    #[test]
+    fn test_inclusion_proof_verifies_against_root() {
+        let mut chain = SemanticBlockchain::new();
+        let target = make_tx("<div property=\"b\">2</div>", 200, 2);
+        let target_id = target.id();
+        assert!(chain.add_transaction(make_tx("<div property=\"a\">1</div>", 100, 1)));
+        assert!(chain.add_transaction(target));
+        assert!(chain.add_transaction(make_tx("<div property=\"c\">3</div>", 150, 3)));
+        let root = chain.mine_block(b"miner".to_vec(), 10).header.merkle_root;
+        let (index, proof) = chain
+            .generate_inclusion_proof(&target_id)
+            .expect("mined transaction has a proof");
+        assert_eq!(proof.len(), 2);
+        assert!(SemanticBlockchain::verify_inclusion(&target_id, index, &proof, &root));
+        // A tampered proof no longer hashes to the committed root.
+        let mut tampered = proof.clone();
+        tampered[0][0] ^= 1;
+        assert!(!SemanticBlockchain::verify_inclusion(&target_id, index, &tampered, &root));
+        assert_eq!(chain.generate_inclusion_proof(&[9u8; 32]), None);
+    }
+
+    #[test]
+    fn test_merkle_root_is_order_dependent() {
+        let a = make_tx("<div property=\"a\">1</div>", 100, 1);
+        let b = make_tx("<div property=\"b\">2</div>", 200, 2);
+        let forward = SemanticBlockchain::calculate_merkle_root(&[a.clone(), b.clone()]);
+        let reverse = SemanticBlockchain::calculate_merkle_root(&[b, a]);
+        assert_ne!(forward, reverse);
+    }
+
+    #[test]
+    fn test_merkle_proof_for_every_position() {
+        let mut chain = SemanticBlockchain::new();
+        for (i, fee) in [500, 400, 300, 200].iter().enumerate() {
+            let rdfa = format!("<div property=\"p{}\">{}</div>", i, i);
+            assert!(chain.add_transaction(make_tx(&rdfa, *fee, i as u64)));
+        }
+        let block = chain.mine_block(b"miner".to_vec(), 10).clone();
+        let root = block.header.merkle_root;
+        for (index, tx) in block.transactions.iter().enumerate() {
+            let proof = block.merkle_proof(index).expect("index in range");
+            assert!(verify_merkle_proof(&tx.id(), index, &proof, &root));
+            // The same proof fails for the wrong position.
+            assert!(!verify_merkle_proof(&tx.id(), index + 1, &proof, &root));
+        }
+        assert_eq!(block.merkle_proof(4), None);
+    }
+
+    #[test]
+    fn test_mining_is_insertion_order_independent() {
+        let txs = vec![
+            make_tx("<div property=\"a\">1</div>", 100, 1),
+            make_tx("<div property=\"b\">2</div>", 200, 2),
+            make_tx("<div property=\"c\">3</div>", 150, 3),
+        ];
+        let mut forward = SemanticBlockchain::new();
+        for tx in txs.clone() {
+            assert!(forward.add_transaction(tx));
+        }
+        let mut reverse = SemanticBlockchain::new();
+        for tx in txs.into_iter().rev() {
+            assert!(reverse.add_transaction(tx));
+        }
+        let block_a = forward.mine_block(b"miner".to_vec(), 10).clone();
+        let block_b = reverse.mine_block(b"miner".to_vec(), 10).clone();
+        assert_eq!(block_a.transactions, block_b.transactions);
+        assert_eq!(block_a.header.merkle_root, block_b.header.merkle_root);
+        // Highest fee first.
+        assert_eq!(block_a.transactions[0].fee, 200);
+    }
+}
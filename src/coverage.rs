@@ -0,0 +1,820 @@
+//! Ontology coverage over representation spaces.
+//!
+//! An ontology that can encode itself into every space a platform
+//! understands (HTML, JSON, URLs, filenames, ...) survives no matter
+//! which channel the platform leaves open. This module measures how
+//! much of that universal coverage an ontology actually achieves.
+
+/// A representation space an ontology can be encoded into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Space {
+    Html,
+    Css,
+    Url,
+    Json,
+    Sql,
+    GraphQl,
+    Rest,
+    Sparql,
+    Markdown,
+    Yaml,
+    Toml,
+    Protobuf,
+    Xml,
+    Rdf,
+    Variable,
+    Function,
+    Type,
+    Path,
+    Filename,
+    Attribute,
+}
+
+impl Space {
+    /// Every representation space, for exhaustive coverage runs.
+    pub const ALL: [Space; 20] = [
+        Space::Html,
+        Space::Css,
+        Space::Url,
+        Space::Json,
+        Space::Sql,
+        Space::GraphQl,
+        Space::Rest,
+        Space::Sparql,
+        Space::Markdown,
+        Space::Yaml,
+        Space::Toml,
+        Space::Protobuf,
+        Space::Xml,
+        Space::Rdf,
+        Space::Variable,
+        Space::Function,
+        Space::Type,
+        Space::Path,
+        Space::Filename,
+        Space::Attribute,
+    ];
+
+    /// The spaces a term hits when embedded in a web page.
+    pub const fn web_spaces() -> [Space; 4] {
+        [Space::Html, Space::Css, Space::Url, Space::Json]
+    }
+
+    /// Serialization and interchange formats.
+    pub const fn data_spaces() -> [Space; 6] {
+        [
+            Space::Json,
+            Space::Xml,
+            Space::Yaml,
+            Space::Toml,
+            Space::Rdf,
+            Space::Protobuf,
+        ]
+    }
+
+    /// Naming-convention spaces inside source code.
+    pub const fn code_spaces() -> [Space; 5] {
+        [
+            Space::Variable,
+            Space::Function,
+            Space::Type,
+            Space::Path,
+            Space::Filename,
+        ]
+    }
+}
+
+/// An ontology that can project itself into representation spaces and
+/// be recovered from them. Coverage counts a space only when the
+/// projection is isomorphic: `decode(encode(o, s), s) == o`.
+pub trait Ontology: Sized + PartialEq {
+    fn name(&self) -> String;
+
+    /// Encode into `space`; an empty string means the space is
+    /// unsupported.
+    fn encode(&self, space: Space) -> String;
+
+    /// Recover an ontology value from its encoding in `space`.
+    fn decode(encoded: &str, space: Space) -> Self;
+}
+
+/// True if the ontology survives a self-description loop in every
+/// supported space in `spaces`: encoding it, re-parsing the encoding,
+/// and encoding again yields the same value and the same description.
+/// This is what the `meta_circular` flag asserts — the ontology can
+/// describe its own encodings. Unsupported spaces (empty encodings)
+/// don't count against it.
+pub fn is_meta_circular<O: Ontology>(ontology: &O, spaces: &[Space]) -> bool {
+    spaces.iter().all(|&space| {
+        let encoded = ontology.encode(space);
+        if encoded.is_empty() {
+            return true;
+        }
+        let reparsed = O::decode(&encoded, space);
+        reparsed == *ontology && reparsed.encode(space) == encoded
+    })
+}
+
+/// The text between `prefix` and the next `suffix`, if both appear.
+fn between<'a>(text: &'a str, prefix: &str, suffix: &str) -> Option<&'a str> {
+    let start = text.find(prefix)? + prefix.len();
+    let end = text[start..].find(suffix)?;
+    Some(&text[start..start + end])
+}
+
+/// One eRDFa vocabulary term as an ontology: the term name, the action
+/// a processor takes, and the result it produces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ERdfaTerm {
+    pub term: String,
+    pub action: String,
+    pub result: String,
+}
+
+/// The predefined eRDFa 1.0 terms.
+pub mod terms {
+    use super::ERdfaTerm;
+
+    /// `eRDFa:embedded` — unescape the content and extract it.
+    pub fn embedded() -> ERdfaTerm {
+        ERdfaTerm {
+            term: "embedded".to_string(),
+            action: "unescape".to_string(),
+            result: "extract".to_string(),
+        }
+    }
+
+    /// `eRDFa:example` — ignore the content and skip it.
+    pub fn example() -> ERdfaTerm {
+        ERdfaTerm {
+            term: "example".to_string(),
+            action: "ignore".to_string(),
+            result: "skip".to_string(),
+        }
+    }
+}
+
+/// The universal encoder: one naming-convention encoding per space, so
+/// a term survives as a URL, a filename, a CSS selector, or a variable
+/// name even when every other channel is stripped.
+impl ERdfaTerm {
+    pub fn encode_json(&self) -> String {
+        format!(
+            "{{\"term\":\"{}\",\"action\":\"{}\",\"result\":\"{}\"}}",
+            self.term, self.action, self.result
+        )
+    }
+
+    pub fn encode_attribute(&self) -> String {
+        format!(
+            "data-erdfa-term=\"{}\" data-erdfa-action=\"{}\" data-erdfa-result=\"{}\"",
+            self.term, self.action, self.result
+        )
+    }
+
+    pub fn encode_url(&self) -> String {
+        format!(
+            "https://erdfa.org/ns/{}/{}/{}",
+            self.term, self.action, self.result
+        )
+    }
+
+    pub fn encode_path(&self) -> String {
+        format!("/ns/erdfa/{}/{}/{}", self.term, self.action, self.result)
+    }
+
+    pub fn encode_filename(&self) -> String {
+        format!("erdfa-{}-{}-{}.ttl", self.term, self.action, self.result)
+    }
+
+    pub fn encode_css_selector(&self) -> String {
+        format!(
+            ".erdfa-{}[data-action=\"{}\"][data-result=\"{}\"]",
+            self.term, self.action, self.result
+        )
+    }
+
+    pub fn encode_variable(&self) -> String {
+        format!("erdfa_{}_{}_{}", self.term, self.action, self.result)
+    }
+
+    pub fn encode_function_name(&self) -> String {
+        format!("erdfa_term_{}_{}_{}()", self.term, self.action, self.result)
+    }
+
+    pub fn encode_html(&self) -> String {
+        format!(
+            "<div rel=\"eRDFa:{}\" data-action=\"{}\" data-result=\"{}\"></div>",
+            self.term, self.action, self.result
+        )
+    }
+
+    pub fn encode_sql(&self) -> String {
+        format!(
+            "SELECT '{}' AS term, '{}' AS action, '{}' AS result FROM erdfa_terms;",
+            self.term, self.action, self.result
+        )
+    }
+
+    pub fn encode_yaml(&self) -> String {
+        format!(
+            "term: {}\naction: {}\nresult: {}\n",
+            self.term, self.action, self.result
+        )
+    }
+
+    pub fn encode_toml(&self) -> String {
+        format!(
+            "[erdfa]\nterm = \"{}\"\naction = \"{}\"\nresult = \"{}\"\n",
+            self.term, self.action, self.result
+        )
+    }
+
+    pub fn encode_xml(&self) -> String {
+        format!(
+            "<erdfa term=\"{}\" action=\"{}\" result=\"{}\"/>",
+            self.term, self.action, self.result
+        )
+    }
+
+    fn from_parts(term: &str, action: &str, result: &str) -> ERdfaTerm {
+        ERdfaTerm {
+            term: term.to_string(),
+            action: action.to_string(),
+            result: result.to_string(),
+        }
+    }
+
+    /// Parse an encoding back into a term, inverting the corresponding
+    /// `encode_*` method. `None` for unsupported spaces or input that
+    /// doesn't follow the space's shape.
+    fn parse(encoded: &str, space: Space) -> Option<ERdfaTerm> {
+        match space {
+            Space::Html => Some(Self::from_parts(
+                between(encoded, "rel=\"eRDFa:", "\"")?,
+                between(encoded, "data-action=\"", "\"")?,
+                between(encoded, "data-result=\"", "\"")?,
+            )),
+            Space::Css => Some(Self::from_parts(
+                between(encoded, ".erdfa-", "[")?,
+                between(encoded, "data-action=\"", "\"")?,
+                between(encoded, "data-result=\"", "\"")?,
+            )),
+            Space::Url => {
+                let mut parts = encoded.strip_prefix("https://erdfa.org/ns/")?.split('/');
+                Some(Self::from_parts(parts.next()?, parts.next()?, parts.next()?))
+            }
+            Space::Json => Some(Self::from_parts(
+                between(encoded, "\"term\":\"", "\"")?,
+                between(encoded, "\"action\":\"", "\"")?,
+                between(encoded, "\"result\":\"", "\"")?,
+            )),
+            Space::Variable => {
+                let mut parts = encoded.strip_prefix("erdfa_")?.splitn(3, '_');
+                Some(Self::from_parts(parts.next()?, parts.next()?, parts.next()?))
+            }
+            Space::Function => {
+                let inner = encoded.strip_prefix("erdfa_term_")?.strip_suffix("()")?;
+                let mut parts = inner.splitn(3, '_');
+                Some(Self::from_parts(parts.next()?, parts.next()?, parts.next()?))
+            }
+            Space::Path => {
+                let mut parts = encoded.strip_prefix("/ns/erdfa/")?.split('/');
+                Some(Self::from_parts(parts.next()?, parts.next()?, parts.next()?))
+            }
+            Space::Filename => {
+                let inner = encoded.strip_prefix("erdfa-")?.strip_suffix(".ttl")?;
+                let mut parts = inner.splitn(3, '-');
+                Some(Self::from_parts(parts.next()?, parts.next()?, parts.next()?))
+            }
+            Space::Sql => Some(Self::from_parts(
+                between(encoded, "SELECT '", "'")?,
+                between(encoded, "term, '", "'")?,
+                between(encoded, "action, '", "'")?,
+            )),
+            Space::Yaml => Some(Self::from_parts(
+                between(encoded, "term: ", "\n")?,
+                between(encoded, "action: ", "\n")?,
+                between(encoded, "result: ", "\n")?,
+            )),
+            Space::Toml => Some(Self::from_parts(
+                between(encoded, "term = \"", "\"")?,
+                between(encoded, "action = \"", "\"")?,
+                between(encoded, "result = \"", "\"")?,
+            )),
+            Space::Xml => Some(Self::from_parts(
+                between(encoded, " term=\"", "\"")?,
+                between(encoded, " action=\"", "\"")?,
+                between(encoded, " result=\"", "\"")?,
+            )),
+            Space::Attribute => Some(Self::from_parts(
+                between(encoded, "data-erdfa-term=\"", "\"")?,
+                between(encoded, "data-erdfa-action=\"", "\"")?,
+                between(encoded, "data-erdfa-result=\"", "\"")?,
+            )),
+            _ => None,
+        }
+    }
+
+    /// Named inverses of the `encode_*` methods above, completing the
+    /// encoder/decoder pairs that `MonsterSymmetry` only provides for
+    /// URL, path, and variable. Like [`Ontology::decode`], input that
+    /// doesn't follow the encoding's shape falls back to the reference
+    /// term.
+    pub fn decode_json(encoded: &str) -> ERdfaTerm {
+        Self::parse(encoded, Space::Json).unwrap_or_else(terms::embedded)
+    }
+
+    pub fn decode_filename(encoded: &str) -> ERdfaTerm {
+        Self::parse(encoded, Space::Filename).unwrap_or_else(terms::embedded)
+    }
+
+    pub fn decode_attribute(encoded: &str) -> ERdfaTerm {
+        Self::parse(encoded, Space::Attribute).unwrap_or_else(terms::embedded)
+    }
+
+    pub fn decode_function_name(encoded: &str) -> ERdfaTerm {
+        Self::parse(encoded, Space::Function).unwrap_or_else(terms::embedded)
+    }
+}
+
+impl Ontology for ERdfaTerm {
+    fn name(&self) -> String {
+        format!("eRDFa:{}", self.term)
+    }
+
+    fn encode(&self, space: Space) -> String {
+        match space {
+            Space::Html => self.encode_html(),
+            Space::Css => self.encode_css_selector(),
+            Space::Url => self.encode_url(),
+            Space::Json => self.encode_json(),
+            Space::Variable => self.encode_variable(),
+            Space::Function => self.encode_function_name(),
+            Space::Path => self.encode_path(),
+            Space::Filename => self.encode_filename(),
+            Space::Sql => self.encode_sql(),
+            Space::Yaml => self.encode_yaml(),
+            Space::Toml => self.encode_toml(),
+            Space::Xml => self.encode_xml(),
+            // The remaining spaces are not implemented yet.
+            _ => String::new(),
+        }
+    }
+
+    fn decode(encoded: &str, space: Space) -> Self {
+        // The trait is infallible, so unparseable input falls back to
+        // the reference term; the coverage isomorphism check then fails
+        // for whatever produced it instead of silently passing.
+        Self::parse(encoded, space).unwrap_or_else(terms::embedded)
+    }
+}
+
+/// The Monster-symmetry view of a term: the same term decoded out of
+/// several encodings should be the same object from every angle.
+pub struct MonsterSymmetry {
+    pub term: ERdfaTerm,
+}
+
+impl MonsterSymmetry {
+    pub fn new(term: ERdfaTerm) -> Self {
+        MonsterSymmetry { term }
+    }
+
+    /// Reduce raw components to the canonical token list: empty pieces
+    /// and namespace scaffolding (scheme, host, `ns`/`erdfa` markers)
+    /// are dropped and the rest lowercased, so every encoding of the
+    /// same term yields the same tokens regardless of its separators.
+    fn canonical_tokens<'a, I: Iterator<Item = &'a str>>(components: I) -> Vec<String> {
+        components
+            .filter(|component| !component.is_empty())
+            .filter(|component| !matches!(*component, "https:" | "erdfa.org" | "ns" | "erdfa"))
+            .map(str::to_lowercase)
+            .collect()
+    }
+
+    pub fn decode_url(url: &str) -> Vec<String> {
+        Self::canonical_tokens(url.split('/'))
+    }
+
+    pub fn decode_path(path: &str) -> Vec<String> {
+        Self::canonical_tokens(path.split('/'))
+    }
+
+    pub fn decode_variable(variable: &str) -> Vec<String> {
+        Self::canonical_tokens(variable.split('_'))
+    }
+
+    /// Whether the URL, path, and variable encodings all decode to the
+    /// same canonical token list.
+    pub fn verify_invariance(&self) -> bool {
+        let from_url = Self::decode_url(&self.term.encode_url());
+        let from_path = Self::decode_path(&self.term.encode_path());
+        let from_variable = Self::decode_variable(&self.term.encode_variable());
+        from_url == from_path && from_path == from_variable
+    }
+}
+
+/// How completely an ontology covers the representation spaces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoverageClass {
+    Minimal,
+    Low,
+    Medium,
+    High,
+    Maximal,
+}
+
+impl CoverageClass {
+    /// Position in the Minimal < Low < Medium < High < Maximal chain.
+    fn rank(self) -> u8 {
+        match self {
+            CoverageClass::Minimal => 0,
+            CoverageClass::Low => 1,
+            CoverageClass::Medium => 2,
+            CoverageClass::High => 3,
+            CoverageClass::Maximal => 4,
+        }
+    }
+}
+
+impl Ord for CoverageClass {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+impl PartialOrd for CoverageClass {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Result of a coverage run over a set of spaces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverageMetrics {
+    pub total_spaces: usize,
+    pub successful_spaces: usize,
+    pub score: f64,
+    pub self_describing: bool,
+    pub fractal: bool,
+    pub holographic: bool,
+    pub meta_circular: bool,
+}
+
+impl CoverageMetrics {
+    pub fn class(&self) -> CoverageClass {
+        // A NaN score fails every `>=` below and would silently land on
+        // Minimal; an explicitly broken score should not look like a
+        // legitimately minimal one.
+        assert!(!self.score.is_nan(), "coverage score is NaN");
+        if self.score >= 0.95 {
+            CoverageClass::Maximal
+        } else if self.score >= 0.75 {
+            CoverageClass::High
+        } else if self.score >= 0.5 {
+            CoverageClass::Medium
+        } else if self.score >= 0.25 {
+            CoverageClass::Low
+        } else {
+            CoverageClass::Minimal
+        }
+    }
+
+    /// Attach the structural properties a coverage run cannot measure
+    /// by itself. Builder-style so callers set the flags in one place
+    /// instead of poking the public fields:
+    ///
+    /// ```ignore
+    /// let metrics = calculate_coverage(&term, &Space::ALL)
+    ///     .with_properties(detect_self_describing(&term), false, false,
+    ///                      is_meta_circular(&term, &Space::ALL));
+    /// ```
+    ///
+    /// The flags never influence [`class`](Self::class), which is a
+    /// function of the score alone.
+    pub fn with_properties(
+        mut self,
+        self_describing: bool,
+        fractal: bool,
+        holographic: bool,
+        meta_circular: bool,
+    ) -> CoverageMetrics {
+        self.self_describing = self_describing;
+        self.fractal = fractal;
+        self.holographic = holographic;
+        self.meta_circular = meta_circular;
+        self
+    }
+}
+
+/// Whether the ontology describes itself: at least one space's encoding
+/// carries the ontology's own name, so a consumer can tell what it is
+/// looking at from the encoding alone.
+pub fn detect_self_describing<O: Ontology>(ontology: &O) -> bool {
+    Space::ALL
+        .iter()
+        .any(|&space| ontology.encode(space).contains(&ontology.name()))
+}
+
+/// Measure how many of `spaces` the ontology round-trips through
+/// isomorphically. An empty space set scores 0.0 — zero coverage over
+/// nothing — rather than dividing into `NaN`. Every space counts
+/// equally; see [`calculate_weighted_coverage`] when some spaces matter
+/// more.
+pub fn calculate_coverage<O: Ontology>(ontology: &O, spaces: &[Space]) -> CoverageMetrics {
+    let uniform: Vec<(Space, f64)> = spaces.iter().map(|&space| (space, 1.0)).collect();
+    calculate_weighted_coverage(ontology, &uniform)
+}
+
+/// [`calculate_coverage`] with a per-space weight: the score is the
+/// weight of the round-tripping spaces over the total weight, so a
+/// failure in a heavily weighted space (RDF for a triple store, say)
+/// drags the score down more than one in a marginal space. The
+/// unweighted counts are still reported; only `score` — and therefore
+/// [`CoverageMetrics::class`] — sees the weights.
+pub fn calculate_weighted_coverage<O: Ontology>(
+    ontology: &O,
+    spaces: &[(Space, f64)],
+) -> CoverageMetrics {
+    let mut successful = 0;
+    let mut successful_weight = 0.0;
+    let mut total_weight = 0.0;
+    for &(space, weight) in spaces {
+        total_weight += weight;
+        let encoded = ontology.encode(space);
+        if !encoded.is_empty() && O::decode(&encoded, space) == *ontology {
+            successful += 1;
+            successful_weight += weight;
+        }
+    }
+    let score = if total_weight > 0.0 {
+        successful_weight / total_weight
+    } else {
+        0.0
+    };
+    CoverageMetrics {
+        total_spaces: spaces.len(),
+        successful_spaces: successful,
+        score,
+        // The structural properties are attached afterwards through
+        // `with_properties`.
+        self_describing: false,
+        fractal: false,
+        holographic: false,
+        meta_circular: false,
+    }
+}
+
+/// Define a symmetric term accessor: a zero-argument function named
+/// `$name` returning the [`ERdfaTerm`] spelled by the remaining three
+/// identifiers. The caller picks the function name, since declarative
+/// macros cannot concatenate identifiers without the `paste` crate.
+///
+/// ```ignore
+/// erdfa_symmetric_term!(erdfa_term_embedded, embedded, unescape, extract);
+/// assert_eq!(erdfa_term_embedded().term, "embedded");
+/// ```
+#[macro_export]
+macro_rules! erdfa_symmetric_term {
+    ($name:ident, $term:ident, $action:ident, $result:ident) => {
+        pub fn $name() -> $crate::coverage::ERdfaTerm {
+            $crate::coverage::ERdfaTerm {
+                term: stringify!($term).to_string(),
+                action: stringify!($action).to_string(),
+                result: stringify!($result).to_string(),
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    erdfa_symmetric_term!(erdfa_term_embedded, embedded, unescape, extract);
+
+    #[test]
+    fn test_symmetric_term_macro_generates_accessor() {
+        let term = erdfa_term_embedded();
+        assert_eq!(term, terms::embedded());
+        assert_eq!(term.encode_variable(), "erdfa_embedded_unescape_extract");
+    }
+
+    #[test]
+    fn test_coverage_class_ordering() {
+        assert!(CoverageClass::Maximal > CoverageClass::High);
+        assert!(CoverageClass::Minimal < CoverageClass::Low);
+        assert!(CoverageClass::Medium >= CoverageClass::Medium);
+        let mut classes = vec![
+            CoverageClass::High,
+            CoverageClass::Minimal,
+            CoverageClass::Maximal,
+            CoverageClass::Medium,
+            CoverageClass::Low,
+        ];
+        classes.sort();
+        assert_eq!(
+            classes,
+            vec![
+                CoverageClass::Minimal,
+                CoverageClass::Low,
+                CoverageClass::Medium,
+                CoverageClass::High,
+                CoverageClass::Maximal,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_class_threshold_policy_comparison() {
+        let metrics = calculate_coverage(
+            &terms::embedded(),
+            &[Space::Html, Space::Json, Space::Url, Space::Filename],
+        );
+        // The policy-style comparison the ordering exists for.
+        assert!(metrics.class() >= CoverageClass::High);
+    }
+
+    #[test]
+    fn test_embedded_coverage_over_core_spaces() {
+        let core = [
+            Space::Html,
+            Space::Css,
+            Space::Url,
+            Space::Json,
+            Space::Variable,
+            Space::Function,
+            Space::Path,
+            Space::Filename,
+        ];
+        let metrics = calculate_coverage(&terms::embedded(), &core);
+        assert_eq!(metrics.total_spaces, 8);
+        assert_eq!(metrics.successful_spaces, 8);
+        assert_eq!(metrics.class(), CoverageClass::Maximal);
+    }
+
+    #[test]
+    fn test_data_spaces_round_trip_and_lift_total_coverage() {
+        let embedded = terms::embedded();
+        for space in [Space::Sql, Space::Yaml, Space::Toml, Space::Xml] {
+            let encoded = embedded.encode(space);
+            assert!(!encoded.is_empty(), "{:?} should encode", space);
+            assert_eq!(ERdfaTerm::decode(&encoded, space), embedded);
+        }
+        // 12 of the 20 spaces now round-trip isomorphically.
+        let metrics = calculate_coverage(&embedded, &Space::ALL);
+        assert_eq!(metrics.successful_spaces, 12);
+        assert!(metrics.score >= 0.6);
+        assert_eq!(metrics.class(), CoverageClass::Medium);
+    }
+
+    #[test]
+    fn test_space_presets_contain_expected_variants() {
+        assert_eq!(
+            Space::web_spaces(),
+            [Space::Html, Space::Css, Space::Url, Space::Json]
+        );
+        assert_eq!(
+            Space::data_spaces(),
+            [
+                Space::Json,
+                Space::Xml,
+                Space::Yaml,
+                Space::Toml,
+                Space::Rdf,
+                Space::Protobuf,
+            ]
+        );
+        assert_eq!(
+            Space::code_spaces(),
+            [
+                Space::Variable,
+                Space::Function,
+                Space::Type,
+                Space::Path,
+                Space::Filename,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_coverage_over_presets() {
+        let embedded = terms::embedded();
+        // Every web space round-trips.
+        let web = calculate_coverage(&embedded, &Space::web_spaces());
+        assert_eq!(web.successful_spaces, 4);
+        assert_eq!(web.class(), CoverageClass::Maximal);
+        // Type names have no encoder yet, so one code space is missed.
+        let code = calculate_coverage(&embedded, &Space::code_spaces());
+        assert_eq!(code.total_spaces, 5);
+        assert_eq!(code.successful_spaces, 4);
+    }
+
+    #[test]
+    fn test_named_decoders_reverse_their_encoders() {
+        for term in [terms::embedded(), terms::example()] {
+            assert_eq!(ERdfaTerm::decode_json(&term.encode_json()), term);
+            assert_eq!(ERdfaTerm::decode_filename(&term.encode_filename()), term);
+            assert_eq!(ERdfaTerm::decode_attribute(&term.encode_attribute()), term);
+            assert_eq!(
+                ERdfaTerm::decode_function_name(&term.encode_function_name()),
+                term
+            );
+        }
+        // Malformed input falls back to the reference term.
+        assert_eq!(ERdfaTerm::decode_json("not json"), terms::embedded());
+    }
+
+    #[test]
+    fn test_weighted_coverage_shifts_class() {
+        let embedded = terms::embedded();
+        // JSON round-trips, RDF is unsupported. Equal weights: half
+        // covered, Medium.
+        let even = calculate_weighted_coverage(&embedded, &[(Space::Json, 1.0), (Space::Rdf, 1.0)]);
+        assert_eq!(even.successful_spaces, 1);
+        assert_eq!(even.class(), CoverageClass::Medium);
+        // Weighting RDF ninefold makes the missing space dominate.
+        let skewed =
+            calculate_weighted_coverage(&embedded, &[(Space::Json, 1.0), (Space::Rdf, 9.0)]);
+        assert_eq!(skewed.successful_spaces, 1);
+        assert_eq!(skewed.class(), CoverageClass::Minimal);
+        // Uniform weights reproduce the unweighted score exactly.
+        let uniform: Vec<(Space, f64)> = Space::ALL.iter().map(|&s| (s, 1.0)).collect();
+        assert_eq!(
+            calculate_weighted_coverage(&embedded, &uniform),
+            calculate_coverage(&embedded, &Space::ALL)
+        );
+    }
+
+    #[test]
+    fn test_with_properties_sets_flags_without_touching_class() {
+        let embedded = terms::embedded();
+        let bare = calculate_coverage(&embedded, &Space::ALL);
+        assert!(!bare.self_describing && !bare.meta_circular);
+        let dressed = bare.clone().with_properties(
+            detect_self_describing(&embedded),
+            true,
+            false,
+            is_meta_circular(&embedded, &Space::ALL),
+        );
+        // The HTML encoding carries "eRDFa:embedded" verbatim.
+        assert!(dressed.self_describing);
+        assert!(dressed.fractal);
+        assert!(!dressed.holographic);
+        assert!(dressed.meta_circular);
+        // Classification is a function of the score alone.
+        assert_eq!(dressed.class(), bare.class());
+        assert_eq!(dressed.score, bare.score);
+    }
+
+    #[test]
+    fn test_empty_space_set_scores_zero_not_nan() {
+        let metrics = calculate_coverage(&terms::embedded(), &[]);
+        assert_eq!(metrics.total_spaces, 0);
+        assert_eq!(metrics.successful_spaces, 0);
+        assert_eq!(metrics.score, 0.0);
+        assert_eq!(metrics.class(), CoverageClass::Minimal);
+    }
+
+    #[test]
+    fn test_example_term_scores_like_embedded() {
+        // With the old hardcoded decode, every space "recovered" the
+        // embedded term and example scored zero.
+        let example = terms::example();
+        for space in Space::ALL {
+            let encoded = example.encode(space);
+            if !encoded.is_empty() {
+                assert_eq!(ERdfaTerm::decode(&encoded, space), example, "{:?}", space);
+            }
+        }
+        let embedded_metrics = calculate_coverage(&terms::embedded(), &Space::ALL);
+        let example_metrics = calculate_coverage(&example, &Space::ALL);
+        assert_eq!(
+            example_metrics.successful_spaces,
+            embedded_metrics.successful_spaces
+        );
+    }
+
+    #[test]
+    fn test_monster_symmetry_invariance_for_predefined_terms() {
+        for term in [terms::embedded(), terms::example()] {
+            let symmetry = MonsterSymmetry::new(term.clone());
+            assert!(symmetry.verify_invariance(), "{}", term.term);
+            // The canonical view is exactly the term's three tokens.
+            assert_eq!(
+                MonsterSymmetry::decode_url(&term.encode_url()),
+                vec![term.term, term.action, term.result]
+            );
+        }
+    }
+
+    #[test]
+    fn test_embedded_term_is_meta_circular() {
+        // The embedded term describes the eRDFa vocabulary itself, so
+        // its self-description loop must close in every space it
+        // supports — including the ones it doesn't encode into, which
+        // are skipped rather than failed.
+        assert!(is_meta_circular(&terms::embedded(), &Space::ALL));
+    }
+}
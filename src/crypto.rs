@@ -0,0 +1,904 @@
+//! Cryptographic layers under the stego transports.
+//!
+//! Payloads can be lattice-encoded with a shared secret, expanded with
+//! Reed-Solomon parity, spread over independent channels, and accompanied
+//! by an extraction witness committing to the hidden data.
+
+use crate::shards::{gf_inv, gf_mul};
+
+/// SHA-256 round constants.
+const SHA256_K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Hash arbitrary bytes into a 32-byte commitment.
+///
+/// This is SHA-256, implemented here so commitments stay dependency-free.
+/// The old 32-slot XOR fold collided on trivially permuted inputs, which
+/// let [`ExtractionWitness::verify`] accept forged data; commitment
+/// equality now implies collision-resistant equality of the inputs.
+pub(crate) fn simple_hash(data: &[u8]) -> [u8; 32] {
+    let mut state: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+    // Standard Merkle-Damgård padding: 0x80, zeros, 64-bit length.
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&(data.len() as u64 * 8).to_be_bytes());
+
+    for block in message.chunks_exact(64) {
+        let mut schedule = [0u32; 64];
+        for (word, bytes) in schedule.iter_mut().zip(block.chunks_exact(4)) {
+            *word = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        for i in 16..64 {
+            let s0 = schedule[i - 15].rotate_right(7)
+                ^ schedule[i - 15].rotate_right(18)
+                ^ (schedule[i - 15] >> 3);
+            let s1 = schedule[i - 2].rotate_right(17)
+                ^ schedule[i - 2].rotate_right(19)
+                ^ (schedule[i - 2] >> 10);
+            schedule[i] = schedule[i - 16]
+                .wrapping_add(s0)
+                .wrapping_add(schedule[i - 7])
+                .wrapping_add(s1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+        for i in 0..64 {
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let temp1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(SHA256_K[i])
+                .wrapping_add(schedule[i]);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            h = g;
+            g = f;
+            f = e;
+            e = d.wrapping_add(temp1);
+            d = c;
+            c = b;
+            b = a;
+            a = temp1.wrapping_add(s0).wrapping_add(maj);
+        }
+        for (lane, mixed) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+            *lane = lane.wrapping_add(mixed);
+        }
+    }
+
+    let mut hash = [0u8; 32];
+    for (bytes, word) in hash.chunks_exact_mut(4).zip(state) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+    hash
+}
+
+/// Commits to extracted data and which channels carried it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractionWitness {
+    pub commitment: [u8; 32],
+    pub proof: Vec<u8>,
+    pub channels_used: Vec<usize>,
+}
+
+impl ExtractionWitness {
+    pub fn generate(data: &[u8], channels_used: Vec<usize>) -> Self {
+        let commitment = simple_hash(data);
+        let proof = Self::generate_proof(data, &channels_used);
+        ExtractionWitness {
+            commitment,
+            proof,
+            channels_used,
+        }
+    }
+
+    fn generate_proof(data: &[u8], channels: &[usize]) -> Vec<u8> {
+        data.iter()
+            .enumerate()
+            .map(|(i, &byte)| {
+                let channel = if channels.is_empty() {
+                    0
+                } else {
+                    channels[i % channels.len()]
+                };
+                byte ^ channel as u8
+            })
+            .collect()
+    }
+
+    /// Commit to the presence of `predicate` in `data` without
+    /// revealing the rest of the payload: the proof binds the payload
+    /// commitment to the predicate, so a verifier holding only the
+    /// witness can check a claimed predicate with
+    /// [`verify_predicate`](Self::verify_predicate). `None` when the
+    /// payload does not carry the predicate as a `property` attribute.
+    pub fn generate_for_predicate(data: &[u8], predicate: &str) -> Option<ExtractionWitness> {
+        let marker = format!("property=\"{}\"", predicate);
+        if !String::from_utf8_lossy(data).contains(&marker) {
+            return None;
+        }
+        let commitment = simple_hash(data);
+        let mut bound = commitment.to_vec();
+        bound.extend_from_slice(predicate.as_bytes());
+        Some(ExtractionWitness {
+            commitment,
+            proof: simple_hash(&bound).to_vec(),
+            channels_used: Vec::new(),
+        })
+    }
+
+    /// Check that this witness proves the presence of `predicate`,
+    /// without access to the payload the commitment covers. Only
+    /// witnesses from
+    /// [`generate_for_predicate`](Self::generate_for_predicate) carry a
+    /// predicate-bound proof; ordinary extraction witnesses fail this
+    /// for every predicate.
+    pub fn verify_predicate(&self, predicate: &str) -> bool {
+        let mut bound = self.commitment.to_vec();
+        bound.extend_from_slice(predicate.as_bytes());
+        self.proof == simple_hash(&bound)
+    }
+
+    /// Check `data` against both halves of the witness: the commitment
+    /// must match its hash, and the proof must be exactly what
+    /// generation derives from `data` and `channels_used`. A witness
+    /// whose proof was tampered with — or whose channel list no longer
+    /// matches the one it was generated for — is rejected even when the
+    /// commitment still holds.
+    pub fn verify(&self, data: &[u8]) -> bool {
+        simple_hash(data) == self.commitment
+            && Self::generate_proof(data, &self.channels_used) == self.proof
+    }
+
+    /// Verify many witnesses at once, returning one result per entry in
+    /// input order. Equivalent to calling [`verify`](Self::verify) for
+    /// each pair, but gives the hash backend one place to amortize
+    /// setup or parallelize.
+    pub fn verify_batch(witnesses: &[(&ExtractionWitness, &[u8])]) -> Vec<bool> {
+        witnesses
+            .iter()
+            .map(|(witness, data)| witness.verify(data))
+            .collect()
+    }
+}
+
+/// Toy LWE-style encoder mixing payload bytes with a shared secret.
+///
+/// Each byte is scaled into the high bits of the residue ring and
+/// masked with a per-position value derived from the secret:
+/// `c_i = (b_i * Δ + i * key + e_i) mod m`, where `Δ = ⌊m / 256⌋` and
+/// the noise `e_i < Δ` lives strictly below the message bits. Decode
+/// subtracts the mask and rounds the noise away by dividing by `Δ`, so
+/// `decode(encode(data, s), s) == data` for any payload as long as
+/// `modulus > 255`. With a large prime modulus such as 7681 the
+/// message and noise occupy disjoint ranges, as in real LWE; at the
+/// minimum modulus `Δ = 1` and the noise degenerates to zero.
+pub struct LatticeEncoder {
+    pub dimension: usize,
+    pub modulus: u32,
+}
+
+impl LatticeEncoder {
+    pub fn new(dimension: usize, modulus: u32) -> Self {
+        LatticeEncoder { dimension, modulus }
+    }
+
+    /// The message scaling factor Δ; bytes occupy multiples of it.
+    fn delta(&self) -> u32 {
+        (self.modulus / 256).max(1)
+    }
+
+    /// The additive mask for element `i`.
+    fn mask(&self, key: u32, i: usize) -> u32 {
+        ((i as u64 * key as u64) % self.modulus as u64) as u32
+    }
+
+    /// Deterministic sub-Δ noise for element `i`.
+    fn noise(&self, i: usize) -> u32 {
+        ((i as u64).wrapping_mul(0x9e37_79b9) % self.delta() as u64) as u32
+    }
+
+    pub fn encode(&self, data: &[u8], secret: &[u8]) -> Vec<u32> {
+        let key: u32 = secret.iter().map(|&s| s as u32).sum();
+        let delta = self.delta();
+        data.iter()
+            .enumerate()
+            .map(|(i, &byte)| {
+                (byte as u32 * delta + self.noise(i) + self.mask(key, i)) % self.modulus
+            })
+            .collect()
+    }
+
+    pub fn decode(&self, ciphertext: &[u32], secret: &[u8]) -> Vec<u8> {
+        let key: u32 = secret.iter().map(|&s| s as u32).sum();
+        let delta = self.delta();
+        ciphertext
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| (((c + self.modulus - self.mask(key, i)) % self.modulus) / delta) as u8)
+            .collect()
+    }
+
+    /// Batch [`encode`](Self::encode): one pass over the whole buffer
+    /// with the key derived once and the output preallocated. Produces
+    /// byte-identical output to the per-byte path.
+    pub fn encode_bytes(&self, data: &[u8], secret: &[u8]) -> Vec<u32> {
+        let key: u32 = secret.iter().map(|&s| s as u32).sum();
+        let delta = self.delta();
+        let mut out = Vec::with_capacity(data.len());
+        for (i, &byte) in data.iter().enumerate() {
+            out.push((byte as u32 * delta + self.noise(i) + self.mask(key, i)) % self.modulus);
+        }
+        out
+    }
+
+    /// Batch [`decode`](Self::decode), the inverse of
+    /// [`encode_bytes`](Self::encode_bytes).
+    pub fn decode_bytes(&self, ciphertext: &[u32], secret: &[u8]) -> Vec<u8> {
+        let key: u32 = secret.iter().map(|&s| s as u32).sum();
+        let delta = self.delta();
+        let mut out = Vec::with_capacity(ciphertext.len());
+        for (i, &c) in ciphertext.iter().enumerate() {
+            out.push((((c + self.modulus - self.mask(key, i)) % self.modulus) / delta) as u8);
+        }
+        out
+    }
+}
+
+/// Evaluate the polynomial interpolated through `points` at `x`, in
+/// GF(2^8). `None` if two points share an x-coordinate.
+fn gf_lagrange_eval(points: &[(u8, u8)], x: u8) -> Option<u8> {
+    let mut acc = 0u8;
+    for (i, &(xi, yi)) in points.iter().enumerate() {
+        let mut basis = 1u8;
+        for (j, &(xj, _)) in points.iter().enumerate() {
+            if i != j {
+                let denominator = xi ^ xj;
+                if denominator == 0 {
+                    return None;
+                }
+                basis = gf_mul(basis, gf_mul(x ^ xj, gf_inv(denominator)));
+            }
+        }
+        acc ^= gf_mul(yi, basis);
+    }
+    Some(acc)
+}
+
+/// Systematic Reed-Solomon expansion over GF(2^8): the data symbols are
+/// the values of a degree `< data_symbols` polynomial at evaluation
+/// points `1..=data_symbols`, and each parity symbol is the same
+/// polynomial at a further distinct point. Any `data_symbols` surviving
+/// symbols therefore determine the codeword (see
+/// [`recover_from_erasures`](Self::recover_from_erasures)).
+pub struct ReedSolomonEncoder {
+    pub data_symbols: usize,
+    pub total_symbols: usize,
+}
+
+impl ReedSolomonEncoder {
+    /// `total_symbols` is capped at 255, the number of distinct nonzero
+    /// evaluation points in GF(2^8).
+    pub fn new(data_symbols: usize, total_symbols: usize) -> Self {
+        assert!(total_symbols <= 255, "GF(256) has only 255 nonzero points");
+        ReedSolomonEncoder {
+            data_symbols,
+            total_symbols,
+        }
+    }
+
+    /// The evaluation point for symbol index `i`; zero is reserved.
+    fn x_for(index: usize) -> u8 {
+        (index + 1) as u8
+    }
+
+    pub fn encode(&self, data: &[u8]) -> Vec<u8> {
+        let mut symbols = data.to_vec();
+        symbols.resize(self.data_symbols, 0);
+        let points: Vec<(u8, u8)> = symbols
+            .iter()
+            .enumerate()
+            .map(|(i, &y)| (Self::x_for(i), y))
+            .collect();
+        for i in self.data_symbols..self.total_symbols {
+            let parity = gf_lagrange_eval(&points, Self::x_for(i))
+                .expect("data evaluation points are distinct");
+            symbols.push(parity);
+        }
+        symbols
+    }
+
+    /// Return the data symbols if the received word is a valid
+    /// codeword.
+    ///
+    /// Every parity symbol is recomputed from the received data
+    /// symbols and compared, so any corrupted symbol — data or parity —
+    /// is detected and yields `None`.
+    pub fn decode(&self, symbols: &[u8]) -> Option<Vec<u8>> {
+        if symbols.len() < self.total_symbols {
+            return None;
+        }
+        let data = &symbols[..self.data_symbols];
+        if self.encode(data)[self.data_symbols..] != symbols[self.data_symbols..self.total_symbols]
+        {
+            return None;
+        }
+        Some(data.to_vec())
+    }
+
+    /// Rebuild the data symbols from any `data_symbols` surviving
+    /// symbols, supplied as `(codeword_index, value)` pairs. `None` if
+    /// too few survive or an index repeats or is out of range.
+    pub fn recover_from_erasures(&self, survivors: &[(usize, u8)]) -> Option<Vec<u8>> {
+        if survivors.len() < self.data_symbols
+            || survivors.iter().any(|&(i, _)| i >= self.total_symbols)
+        {
+            return None;
+        }
+        let points: Vec<(u8, u8)> = survivors[..self.data_symbols]
+            .iter()
+            .map(|&(i, y)| (Self::x_for(i), y))
+            .collect();
+        (0..self.data_symbols)
+            .map(|i| gf_lagrange_eval(&points, Self::x_for(i)))
+            .collect()
+    }
+}
+
+/// Drop the symbols at positions `lose` from a codeword, returning the
+/// survivors paired with their codeword indices — exactly the shape
+/// [`ReedSolomonEncoder::recover_from_erasures`] consumes. A test
+/// utility for resilience checks, not part of the normal pipeline.
+#[cfg(feature = "test-util")]
+pub fn simulate_loss(symbols: &[u8], lose: &[usize]) -> Vec<(usize, u8)> {
+    symbols
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !lose.contains(i))
+        .map(|(i, &symbol)| (i, symbol))
+        .collect()
+}
+
+/// One channel's bytes with enough header to reassemble the matrix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelPacket {
+    pub channel_id: usize,
+    pub total_channels: usize,
+    pub payload: Vec<u8>,
+}
+
+/// Errors reassembling a [`ChannelMatrix`] from packets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChannelPacketError {
+    Empty,
+    InconsistentTotals,
+    ChannelOutOfRange(usize),
+    DuplicateChannel(usize),
+}
+
+/// Symbols spread round-robin over independent transport channels.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelMatrix {
+    pub channels: Vec<Vec<u8>>,
+}
+
+impl ChannelMatrix {
+    pub fn new(channel_count: usize) -> Self {
+        ChannelMatrix {
+            channels: vec![Vec::new(); channel_count],
+        }
+    }
+
+    pub fn distribute(&mut self, symbols: &[u8]) {
+        let count = self.channels.len();
+        if count == 0 {
+            return;
+        }
+        for (i, &symbol) in symbols.iter().enumerate() {
+            self.channels[i % count].push(symbol);
+        }
+    }
+
+    /// Serialize each channel as an independently transmittable packet.
+    pub fn to_channel_packets(&self) -> Vec<ChannelPacket> {
+        let total_channels = self.channels.len();
+        self.channels
+            .iter()
+            .enumerate()
+            .map(|(channel_id, payload)| ChannelPacket {
+                channel_id,
+                total_channels,
+                payload: payload.clone(),
+            })
+            .collect()
+    }
+
+    /// Reassemble a matrix from any subset of its packets. Channels
+    /// whose packet was lost come back empty; callers can treat them as
+    /// erasures.
+    pub fn from_packets(packets: &[ChannelPacket]) -> Result<ChannelMatrix, ChannelPacketError> {
+        let total = packets.first().ok_or(ChannelPacketError::Empty)?.total_channels;
+        if packets.iter().any(|p| p.total_channels != total) {
+            return Err(ChannelPacketError::InconsistentTotals);
+        }
+        let mut channels = vec![Vec::new(); total];
+        let mut seen = vec![false; total];
+        for packet in packets {
+            if packet.channel_id >= total {
+                return Err(ChannelPacketError::ChannelOutOfRange(packet.channel_id));
+            }
+            if seen[packet.channel_id] {
+                return Err(ChannelPacketError::DuplicateChannel(packet.channel_id));
+            }
+            seen[packet.channel_id] = true;
+            channels[packet.channel_id] = packet.payload.clone();
+        }
+        Ok(ChannelMatrix { channels })
+    }
+
+    /// Interleave the requested channels back into sequence order,
+    /// reversing the round-robin of [`distribute`](Self::distribute):
+    /// round `r` contributes the `r`-th symbol of each requested
+    /// channel in turn. With the full channel set this reproduces the
+    /// original symbol sequence exactly; with a subset in ascending
+    /// order it yields the surviving symbols in their original relative
+    /// order, which callers must treat as a word with erasures.
+    pub fn extract(&self, which: &[usize]) -> Vec<u8> {
+        let rounds = which
+            .iter()
+            .filter_map(|&i| self.channels.get(i))
+            .map(Vec::len)
+            .max()
+            .unwrap_or(0);
+        let mut symbols = Vec::new();
+        for round in 0..rounds {
+            for &i in which {
+                if let Some(&symbol) = self.channels.get(i).and_then(|channel| channel.get(round)) {
+                    symbols.push(symbol);
+                }
+            }
+        }
+        symbols
+    }
+}
+
+/// The combined pipeline: Reed-Solomon expansion over channel spreading,
+/// witnessed by an [`ExtractionWitness`].
+pub struct CryptoStegoSystem {
+    pub lattice: LatticeEncoder,
+    pub rs: ReedSolomonEncoder,
+    channel_count: usize,
+}
+
+impl CryptoStegoSystem {
+    /// `modulus` is handed to the lattice layer; pick a prime well
+    /// above 256 (7681 is the usual choice) so the message scales into
+    /// the high bits and noise cannot disturb decoding.
+    pub fn new(channel_count: usize, modulus: u32) -> Self {
+        CryptoStegoSystem {
+            lattice: LatticeEncoder::new(8, modulus),
+            rs: ReedSolomonEncoder::new(8, 12),
+            channel_count,
+        }
+    }
+
+    /// Number of distinct channel subsets an attacker must consider.
+    pub fn total_capacity(&self) -> u64 {
+        1u64 << self.channel_count
+    }
+
+    pub fn encode(&self, data: &[u8]) -> (ChannelMatrix, ExtractionWitness) {
+        let mut symbols = Vec::new();
+        for chunk in data.chunks(self.rs.data_symbols) {
+            symbols.extend(self.rs.encode(chunk));
+        }
+        let mut matrix = ChannelMatrix::new(self.channel_count);
+        matrix.distribute(&symbols);
+        let witness = ExtractionWitness::generate(data, (0..self.channel_count).collect());
+        (matrix, witness)
+    }
+
+    /// Recover the payload from whatever channels survived.
+    ///
+    /// The witness names the channels that carried the payload; any of
+    /// them that came back empty (a lost packet, say) are treated as
+    /// erasures, and each Reed-Solomon block is rebuilt from its
+    /// surviving symbols. Up to `total - data` symbols per block may be
+    /// missing — losing whole channels is fine as long as each block
+    /// keeps a quorum. Complete blocks still go through the checked
+    /// [`ReedSolomonEncoder::decode`] so corruption is detected.
+    pub fn decode(
+        &self,
+        matrix: &ChannelMatrix,
+        witness: &ExtractionWitness,
+        original_len: usize,
+    ) -> Option<Vec<u8>> {
+        // An empty payload produced no symbols; recover it without
+        // touching the RS chunking paths.
+        if original_len == 0 {
+            return witness.verify(&[]).then(Vec::new);
+        }
+        let count = matrix.channels.len();
+        if count == 0 {
+            return None;
+        }
+        let total = self.rs.total_symbols;
+        let blocks = original_len.div_ceil(self.rs.data_symbols);
+        let mut data = Vec::new();
+        for block in 0..blocks {
+            // Invert the round-robin: symbol `j` went to channel
+            // `j % count` at depth `j / count`.
+            let survivors: Vec<(usize, u8)> = (0..total)
+                .filter_map(|offset| {
+                    let j = block * total + offset;
+                    let channel = j % count;
+                    if !witness.channels_used.contains(&channel) {
+                        return None;
+                    }
+                    matrix.channels[channel].get(j / count).map(|&s| (offset, s))
+                })
+                .collect();
+            if survivors.len() == total {
+                let word: Vec<u8> = survivors.iter().map(|&(_, symbol)| symbol).collect();
+                data.extend(self.rs.decode(&word)?);
+            } else {
+                data.extend(self.rs.recover_from_erasures(&survivors)?);
+            }
+        }
+        data.truncate(original_len);
+        if witness.verify(&data) {
+            Some(data)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simple_hash_matches_sha256_vectors() {
+        let hex = |hash: [u8; 32]| -> String {
+            hash.iter().map(|b| format!("{:02x}", b)).collect()
+        };
+        assert_eq!(
+            hex(simple_hash(b"")),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            hex(simple_hash(b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        // Multi-block input exercises the chaining path.
+        assert_eq!(
+            hex(simple_hash(&[0x61u8; 100])),
+            "2816597888e4a0d3a36b82b83316ab32680eb8f00f8cd3b904d681246d285a0e"
+        );
+    }
+
+    #[test]
+    fn test_commitments_distinct_for_xor_fold_collisions() {
+        // Every pair here collided under the old 32-slot XOR fold:
+        // trailing zeros vanished, and bytes 32 positions apart shared a
+        // slot, so swapping them left the fold unchanged.
+        let mut swapped_far = vec![0u8; 33];
+        swapped_far[0] = b'A';
+        swapped_far[32] = b'B';
+        let mut swapped_near = vec![0u8; 33];
+        swapped_near[0] = b'B';
+        swapped_near[32] = b'A';
+        let inputs: [Vec<u8>; 4] = [
+            Vec::new(),
+            vec![0u8; 32],
+            swapped_far,
+            swapped_near,
+        ];
+        for (i, a) in inputs.iter().enumerate() {
+            for b in &inputs[i + 1..] {
+                assert_ne!(simple_hash(a), simple_hash(b));
+            }
+        }
+    }
+
+    #[test]
+    fn test_witness_verifies_committed_data() {
+        let witness = ExtractionWitness::generate(b"escaped rdfa", vec![0, 1, 2]);
+        assert!(witness.verify(b"escaped rdfa"));
+        assert!(!witness.verify(b"other payload"));
+    }
+
+    #[test]
+    fn test_predicate_witness_selective_disclosure() {
+        let data = b"<div property=\"foaf:name\">Alice</div>";
+        let witness = ExtractionWitness::generate_for_predicate(data, "foaf:name")
+            .expect("predicate is present");
+        assert!(witness.verify_predicate("foaf:name"));
+        // The witness proves exactly the bound predicate, nothing else.
+        assert!(!witness.verify_predicate("foaf:age"));
+        // An absent predicate cannot be witnessed at all.
+        assert_eq!(
+            ExtractionWitness::generate_for_predicate(data, "foaf:age"),
+            None
+        );
+        // An ordinary whole-payload witness proves no predicate.
+        let plain = ExtractionWitness::generate(data, vec![0]);
+        assert!(!plain.verify_predicate("foaf:name"));
+    }
+
+    #[test]
+    fn test_verify_rejects_tampered_proof() {
+        let mut witness = ExtractionWitness::generate(b"escaped rdfa", vec![0, 1, 2]);
+        assert!(witness.verify(b"escaped rdfa"));
+        // Flipping a proof byte leaves the commitment intact but the
+        // witness no longer verifies.
+        witness.proof[0] ^= 0x01;
+        assert!(!witness.verify(b"escaped rdfa"));
+        witness.proof[0] ^= 0x01;
+        // So does rewriting the channel list the proof was bound to.
+        witness.channels_used = vec![3, 4, 5];
+        assert!(!witness.verify(b"escaped rdfa"));
+    }
+
+    #[test]
+    fn test_verify_batch_matches_individual_verify() {
+        let w1 = ExtractionWitness::generate(b"first", vec![0]);
+        let w2 = ExtractionWitness::generate(b"second", vec![1]);
+        let w3 = ExtractionWitness::generate(b"third", vec![2]);
+        let batch: Vec<(&ExtractionWitness, &[u8])> = vec![
+            (&w1, b"first".as_slice()),
+            (&w2, b"tampered".as_slice()),
+            (&w3, b"third".as_slice()),
+        ];
+        let results = ExtractionWitness::verify_batch(&batch);
+        let individual: Vec<bool> = batch.iter().map(|(w, d)| w.verify(d)).collect();
+        assert_eq!(results, individual);
+        assert_eq!(results, vec![true, false, true]);
+    }
+
+    #[test]
+    fn test_lattice_roundtrip_arbitrary_payloads() {
+        let lattice = LatticeEncoder::new(8, 257);
+        let secret = b"shared lattice secret";
+        // A pseudo-random payload covering the full byte range.
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let data: Vec<u8> = (0..256)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect();
+        assert_eq!(lattice.decode(&lattice.encode(&data, secret), secret), data);
+        // The minimum viable modulus still recovers every byte value.
+        let tight = LatticeEncoder::new(8, 256);
+        assert_eq!(tight.decode(&tight.encode(&data, secret), secret), data);
+    }
+
+    #[test]
+    fn test_lattice_batch_matches_per_byte_path() {
+        let lattice = LatticeEncoder::new(8, 257);
+        let data: Vec<u8> = (0..4096u32).map(|i| (i * 31 % 251) as u8).collect();
+        let secret = b"shared lattice secret";
+        let batch = lattice.encode_bytes(&data, secret);
+        assert_eq!(batch, lattice.encode(&data, secret));
+        assert_eq!(
+            lattice.decode_bytes(&batch, secret),
+            lattice.decode(&batch, secret)
+        );
+    }
+
+    #[test]
+    fn test_lattice_large_modulus_rounds_noise_away() {
+        // With q = 7681, Δ = 30, so every ciphertext element carries up
+        // to 29 units of noise below the message bits.
+        let lattice = LatticeEncoder::new(8, 7681);
+        let secret = b"shared lattice secret";
+        let data: Vec<u8> = (0..=255).collect();
+        let ciphertext = lattice.encode(&data, secret);
+        // The noise is really there: stripping the mask leaves residues
+        // that are not all exact multiples of Δ.
+        let key: u32 = secret.iter().map(|&s| s as u32).sum();
+        assert!(ciphertext
+            .iter()
+            .enumerate()
+            .any(|(i, &c)| !((c + 7681 - lattice.mask(key, i)) % 7681).is_multiple_of(30)));
+        // Decode still rounds every byte back exactly.
+        assert_eq!(lattice.decode(&ciphertext, secret), data);
+    }
+
+    #[test]
+    fn test_reed_solomon_symbol_count() {
+        let rs = ReedSolomonEncoder::new(8, 12);
+        let symbols = rs.encode(b"12345678");
+        assert_eq!(symbols.len(), 12);
+    }
+
+    #[test]
+    fn test_reed_solomon_detects_corruption() {
+        let rs = ReedSolomonEncoder::new(8, 12);
+        let symbols = rs.encode(b"12345678");
+        assert_eq!(rs.decode(&symbols).as_deref(), Some(b"12345678".as_slice()));
+        // A flipped data byte no longer matches the parity.
+        let mut corrupted_data = symbols.clone();
+        corrupted_data[3] ^= 0x40;
+        assert_eq!(rs.decode(&corrupted_data), None);
+        // So does a flipped parity byte.
+        let mut corrupted_parity = symbols.clone();
+        corrupted_parity[10] ^= 0x01;
+        assert_eq!(rs.decode(&corrupted_parity), None);
+        // A truncated word can't be checked at all.
+        assert_eq!(rs.decode(&symbols[..10]), None);
+    }
+
+    #[test]
+    fn test_reed_solomon_parity_symbols_are_independent() {
+        // With the old rotate-based parity, rows 8..16 repeated rows
+        // 0..8; evaluation points make every parity symbol distinct
+        // for this payload.
+        let rs = ReedSolomonEncoder::new(8, 20);
+        let symbols = rs.encode(b"12345678");
+        let parity = &symbols[8..];
+        assert_eq!(parity.len(), 12);
+        assert!(
+            parity.iter().any(|&p| p != parity[0]),
+            "parity symbols are trivially equal: {:?}",
+            parity
+        );
+        // In particular row 8 no longer duplicates row 0's fold.
+        assert_ne!(parity[8], parity[0]);
+    }
+
+    #[test]
+    fn test_reed_solomon_recovers_from_any_quorum_of_survivors() {
+        let rs = ReedSolomonEncoder::new(8, 20);
+        let symbols = rs.encode(b"12345678");
+        // Any 8 surviving symbols reconstruct the payload: leading
+        // data, parity only, and strided mixes.
+        let subsets: [Vec<usize>; 4] = [
+            (0..8).collect(),
+            (12..20).collect(),
+            (0..20).step_by(2).take(8).collect(),
+            vec![1, 3, 4, 7, 9, 13, 18, 19],
+        ];
+        for subset in &subsets {
+            let survivors: Vec<(usize, u8)> =
+                subset.iter().map(|&i| (i, symbols[i])).collect();
+            assert_eq!(
+                rs.recover_from_erasures(&survivors).as_deref(),
+                Some(b"12345678".as_slice()),
+                "subset {:?}",
+                subset
+            );
+        }
+        // Seven survivors are not enough.
+        let short: Vec<(usize, u8)> = (0..7).map(|i| (i, symbols[i])).collect();
+        assert_eq!(rs.recover_from_erasures(&short), None);
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn test_simulate_loss_bounds_erasure_tolerance() {
+        let rs = ReedSolomonEncoder::new(8, 20);
+        let symbols = rs.encode(b"12345678");
+        // Losing exactly n - k symbols leaves a recoverable quorum.
+        let max_loss: Vec<usize> = (0..12).map(|i| i * 3 % 20).collect();
+        let survivors = simulate_loss(&symbols, &max_loss);
+        assert_eq!(survivors.len(), 8);
+        assert_eq!(
+            rs.recover_from_erasures(&survivors).as_deref(),
+            Some(b"12345678".as_slice())
+        );
+        // One more loss drops below the quorum.
+        let mut too_many = max_loss.clone();
+        too_many.push(*survivors.first().map(|(i, _)| i).expect("survivors remain"));
+        assert_eq!(rs.recover_from_erasures(&simulate_loss(&symbols, &too_many)), None);
+    }
+
+    #[test]
+    fn test_channel_packets_partial_reassembly() {
+        let mut matrix = ChannelMatrix::new(4);
+        matrix.distribute(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        let mut packets = matrix.to_channel_packets();
+        assert_eq!(packets.len(), 4);
+        // Lose channels 1 and 3 in transit.
+        packets.retain(|p| p.channel_id % 2 == 0);
+        let rebuilt = ChannelMatrix::from_packets(&packets).unwrap();
+        assert_eq!(rebuilt.channels.len(), 4);
+        assert_eq!(rebuilt.channels[0], vec![1, 5]);
+        assert_eq!(rebuilt.channels[2], vec![3, 7]);
+        assert!(rebuilt.channels[1].is_empty());
+        assert_eq!(
+            ChannelMatrix::from_packets(&[]),
+            Err(ChannelPacketError::Empty)
+        );
+    }
+
+    #[test]
+    fn test_extract_reverses_round_robin_interleaving() {
+        let symbols: Vec<u8> = (0..16).collect();
+        let mut matrix = ChannelMatrix::new(4);
+        matrix.distribute(&symbols);
+        // Each channel holds a stride, not a contiguous run.
+        assert_eq!(matrix.channels[1], vec![1, 5, 9, 13]);
+        // Extracting the full channel set reproduces the exact original
+        // order, where the old concatenation gave 0,4,8,12,1,5,...
+        assert_eq!(matrix.extract(&[0, 1, 2, 3]), symbols);
+        // A subset keeps the survivors in original relative order.
+        assert_eq!(matrix.extract(&[0, 2]), vec![0, 2, 4, 6, 8, 10, 12, 14]);
+    }
+
+    #[test]
+    fn test_crypto_stego_multi_channel_roundtrip() {
+        // Before extract reversed the interleaving, any channel count
+        // above one scrambled the RS blocks and decode failed.
+        let system = CryptoStegoSystem::new(3, 7681);
+        let (matrix, witness) = system.encode(b"12345678");
+        assert_eq!(matrix.channels.len(), 3);
+        assert_eq!(system.decode(&matrix, &witness, 8).as_deref(), Some(b"12345678".as_slice()));
+    }
+
+    #[test]
+    fn test_crypto_stego_recovers_after_losing_channels() {
+        let system = CryptoStegoSystem::new(8, 7681);
+        let (matrix, witness) = system.encode(b"12345678");
+        // Two whole channels vanish in transit.
+        let mut packets = matrix.to_channel_packets();
+        packets.retain(|p| p.channel_id != 2 && p.channel_id != 5);
+        let rebuilt = ChannelMatrix::from_packets(&packets).unwrap();
+        assert!(rebuilt.channels[2].is_empty());
+        // The lost symbols are erasures; the RS quorum still holds.
+        assert_eq!(
+            system.decode(&rebuilt, &witness, 8).as_deref(),
+            Some(b"12345678".as_slice())
+        );
+        // Losing five of eight channels leaves too few symbols.
+        let mut packets = matrix.to_channel_packets();
+        packets.retain(|p| p.channel_id >= 5);
+        let rebuilt = ChannelMatrix::from_packets(&packets).unwrap();
+        assert_eq!(system.decode(&rebuilt, &witness, 8), None);
+    }
+
+    #[test]
+    fn test_crypto_stego_empty_payload_roundtrip() {
+        let system = CryptoStegoSystem::new(1, 7681);
+        let (matrix, witness) = system.encode(&[]);
+        // No symbols means no channel bytes, and decode recovers the
+        // empty payload rather than erroring in the chunking paths.
+        assert!(matrix.channels.iter().all(Vec::is_empty));
+        assert_eq!(system.decode(&matrix, &witness, 0), Some(Vec::new()));
+    }
+
+    #[test]
+    fn test_crypto_stego_single_byte_roundtrip() {
+        let system = CryptoStegoSystem::new(1, 7681);
+        let (matrix, witness) = system.encode(b"x");
+        // One byte is padded to a full RS block of 12 symbols.
+        assert_eq!(matrix.channels[0].len(), 12);
+        let decoded = system.decode(&matrix, &witness, 1);
+        assert_eq!(decoded.as_deref(), Some(b"x".as_slice()));
+    }
+
+    #[test]
+    fn test_crypto_stego_single_channel_roundtrip() {
+        let system = CryptoStegoSystem::new(1, 7681);
+        let (matrix, witness) = system.encode(b"12345678");
+        let decoded = system.decode(&matrix, &witness, 8);
+        assert_eq!(decoded.as_deref(), Some(b"12345678".as_slice()));
+    }
+}
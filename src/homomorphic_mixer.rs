@@ -0,0 +1,153 @@
+//! Batch mixing of semantic transactions.
+//!
+//! Submitted transactions are pooled and released in batches, reordered
+//! into canonical id order, so an observer of the mixed output cannot
+//! link a transaction back to its arrival position. Fees are aggregated
+//! per batch rather than exposed per transaction.
+
+use crate::blockchain::SemanticTransaction;
+
+/// Smallest batch worth mixing: below two transactions there is no
+/// anonymity set at all.
+pub const MIN_MIX_SIZE: usize = 2;
+
+/// One released batch of mixed transactions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MixedBatch {
+    /// Transactions in canonical id order, unlinked from arrival order.
+    pub transactions: Vec<SemanticTransaction>,
+    /// Aggregate fee of the batch.
+    pub total_fee: u64,
+    pub mixed_at: u64,
+}
+
+/// Pools transactions until a batch is ready to mix.
+pub struct TransactionPool {
+    /// Pending transactions with their submission time.
+    pending: Vec<(SemanticTransaction, u64)>,
+    pub batch_size: usize,
+    pub mixed: Vec<MixedBatch>,
+}
+
+impl TransactionPool {
+    pub fn new(batch_size: usize) -> Self {
+        TransactionPool {
+            pending: Vec::new(),
+            batch_size,
+            mixed: Vec::new(),
+        }
+    }
+
+    /// Submit a transaction; a full batch mixes immediately.
+    pub fn submit(&mut self, tx: SemanticTransaction, now: u64) {
+        self.pending.push((tx, now));
+        if self.pending.len() >= self.batch_size {
+            self.mix(now);
+        }
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+
+    fn mix(&mut self, now: u64) {
+        let mut transactions: Vec<SemanticTransaction> =
+            self.pending.drain(..).map(|(tx, _)| tx).collect();
+        transactions.sort_by_key(|tx| tx.id());
+        let total_fee = transactions.iter().map(|tx| tx.fee).sum();
+        self.mixed.push(MixedBatch {
+            transactions,
+            total_fee,
+            mixed_at: now,
+        });
+    }
+
+    /// Mix whatever is pending if the oldest submission has waited
+    /// longer than `max_wait`, even below `batch_size` — but never
+    /// fewer than [`MIN_MIX_SIZE`] transactions, since a batch of one
+    /// has no anonymity.
+    pub fn flush_if_stale(&mut self, now: u64, max_wait: u64) {
+        if self.pending.len() < MIN_MIX_SIZE {
+            return;
+        }
+        let oldest = self
+            .pending
+            .iter()
+            .map(|&(_, submitted_at)| submitted_at)
+            .min()
+            .expect("pending is non-empty");
+        if now.saturating_sub(oldest) > max_wait {
+            self.mix(now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::ExtractionWitness;
+
+    fn make_tx(rdfa: &str, fee: u64, timestamp: u64) -> SemanticTransaction {
+        let rdfa_data = rdfa.as_bytes().to_vec();
+        let witness = ExtractionWitness::generate(&rdfa_data, vec![0]);
+        SemanticTransaction {
+            rdfa_data,
+            fee,
+            timestamp,
+            signature: vec![7],
+            witness,
+            depends_on: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_full_batch_mixes_immediately() {
+        let mut pool = TransactionPool::new(2);
+        pool.submit(make_tx("<div property=\"a\">1</div>", 10, 1), 100);
+        assert_eq!(pool.pending_count(), 1);
+        pool.submit(make_tx("<div property=\"b\">2</div>", 20, 2), 101);
+        assert_eq!(pool.pending_count(), 0);
+        assert_eq!(pool.mixed.len(), 1);
+        assert_eq!(pool.mixed[0].total_fee, 30);
+        assert_eq!(pool.mixed[0].mixed_at, 101);
+    }
+
+    #[test]
+    fn test_stale_pending_is_flushed_below_batch_size() {
+        let mut pool = TransactionPool::new(10);
+        pool.submit(make_tx("<div property=\"a\">1</div>", 10, 1), 100);
+        pool.submit(make_tx("<div property=\"b\">2</div>", 20, 2), 150);
+        // Not stale yet: the oldest has waited exactly max_wait.
+        pool.flush_if_stale(200, 100);
+        assert_eq!(pool.pending_count(), 2);
+        pool.flush_if_stale(201, 100);
+        assert_eq!(pool.pending_count(), 0);
+        assert_eq!(pool.mixed.len(), 1);
+        assert_eq!(pool.mixed[0].transactions.len(), 2);
+    }
+
+    #[test]
+    fn test_single_stale_transaction_is_not_mixed_alone() {
+        let mut pool = TransactionPool::new(10);
+        pool.submit(make_tx("<div property=\"a\">1</div>", 10, 1), 100);
+        pool.flush_if_stale(10_000, 100);
+        assert_eq!(pool.pending_count(), 1);
+        assert!(pool.mixed.is_empty());
+    }
+
+    #[test]
+    fn test_mixed_order_is_canonical_not_arrival() {
+        let a = make_tx("<div property=\"a\">1</div>", 10, 1);
+        let b = make_tx("<div property=\"b\">2</div>", 20, 2);
+        let mut forward = TransactionPool::new(2);
+        forward.submit(a.clone(), 100);
+        forward.submit(b.clone(), 101);
+        let mut reverse = TransactionPool::new(2);
+        reverse.submit(b, 100);
+        reverse.submit(a, 101);
+        assert_eq!(
+            forward.mixed[0].transactions,
+            reverse.mixed[0].transactions
+        );
+    }
+}
@@ -0,0 +1,90 @@
+//! Binding to the published Lean 4 ontology dataset.
+//!
+//! The full eRDFa ontology is exported as a machine-checked Lean 4
+//! dump and published as a dataset whose URL pins the dump by content
+//! hash. A consumer can therefore verify that what it downloaded is
+//! the dump the publisher proved things about.
+
+use crate::shards::hash_document;
+
+/// The published ontology dump. The revision segment of the URL is the
+/// dump's content hash, so the location itself commits to the bytes.
+pub const HF_DATASET: &str = "https://huggingface.co/datasets/escaped-rdfa/namespace-ontology/\
+     resolve/9b2f4ac0d1e6573b8a90cf12e4d8b76a015c3f9d2e8b4a67c1d05e9f3a72b841/ontology.lean.json";
+
+/// Hex content hash of a dump.
+fn content_hash(dump: &str) -> String {
+    hash_document(dump.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// First URL path segment that looks like a content hash: 64 hex
+/// characters.
+fn hash_in_url(url: &str) -> Option<&str> {
+    url.split('/')
+        .find(|segment| segment.len() == 64 && segment.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// A Lean 4 dump of the whole ontology, tracked with the content hash
+/// it was loaded under.
+pub struct UniversalOntology {
+    pub dump: String,
+    /// Hex hash of `dump`, computed at construction.
+    pub content_hash: String,
+    /// True only when the dump matched the hash its source published.
+    pub verified: bool,
+}
+
+impl UniversalOntology {
+    /// Wrap a raw JSON dump, computing its content hash. The dump
+    /// stays unverified until compared against a published hash.
+    pub fn from_json(dump: &str) -> Self {
+        UniversalOntology {
+            dump: dump.to_string(),
+            content_hash: content_hash(dump),
+            verified: false,
+        }
+    }
+
+    /// Wrap a dump fetched from `dataset_url`, marking it verified
+    /// only if its content hash matches the hash embedded in the URL.
+    pub fn from_hf_dataset(dump: &str, dataset_url: &str) -> Self {
+        let mut ontology = Self::from_json(dump);
+        ontology.verified = hash_in_url(dataset_url) == Some(ontology.content_hash.as_str());
+        ontology
+    }
+
+    /// Compare the stored content hash against a published one.
+    pub fn verify(&self, expected_hash: &str) -> bool {
+        self.content_hash == expected_hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify_against_expected_hash() {
+        let ontology = UniversalOntology::from_json("{\"terms\":[\"embedded\",\"example\"]}");
+        let expected = ontology.content_hash.clone();
+        assert!(ontology.verify(&expected));
+        assert!(!ontology.verify("9b2f4ac0d1e6573b8a90cf12e4d8b76a015c3f9d2e8b4a67c1d05e9f3a72b841"));
+        assert!(!ontology.verified);
+    }
+
+    #[test]
+    fn test_hf_dataset_url_hash_binding() {
+        let dump = "{\"terms\":[\"embedded\",\"example\"]}";
+        let hash = content_hash(dump);
+        let pinned_url = format!(
+            "https://huggingface.co/datasets/escaped-rdfa/namespace-ontology/resolve/{}/ontology.lean.json",
+            hash
+        );
+        assert!(UniversalOntology::from_hf_dataset(dump, &pinned_url).verified);
+        // The canonical dataset URL pins a different dump.
+        assert!(!UniversalOntology::from_hf_dataset(dump, HF_DATASET).verified);
+    }
+}
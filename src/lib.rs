@@ -0,0 +1,457 @@
+//! Escaped RDFa (eRDFa) namespace processing.
+//!
+//! eRDFa hides RDFa markup inside HTML-escaped text so that hostile
+//! publishing platforms (blog engines, feed rewriters) pass it through
+//! untouched. See `docs/1.0.md` for the namespace definition. This crate
+//! provides the escaping primitives, eRDFa element extraction, and the
+//! experimental transport layers built on top of them.
+
+pub mod acl;
+pub mod blockchain;
+pub mod coverage;
+pub mod crypto;
+pub mod homomorphic_mixer;
+pub mod lean4;
+pub mod modular;
+pub mod shards;
+pub mod stego;
+pub mod zk_migration;
+
+/// Terms defined by the eRDFa 1.0 namespace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Term {
+    /// `<eRDFa:example>` — content is illustrative and must be ignored.
+    Example,
+    /// `<eRDFa:embedded>` — content is escaped RDFa and must be extracted.
+    Embedded,
+}
+
+impl Term {
+    /// The tag name within the eRDFa namespace, e.g. `eRDFa:embedded`.
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Term::Example => "eRDFa:example",
+            Term::Embedded => "eRDFa:embedded",
+        }
+    }
+
+    /// Parse a namespace tag back into a term.
+    pub fn from_tag(tag: &str) -> Option<Term> {
+        match tag {
+            "eRDFa:example" => Some(Term::Example),
+            "eRDFa:embedded" => Some(Term::Embedded),
+            _ => None,
+        }
+    }
+
+    /// Every term in the namespace, for exhaustive dispatch tables and
+    /// coverage checks.
+    pub fn all() -> &'static [Term] {
+        &[Term::Example, Term::Embedded]
+    }
+}
+
+/// Outcome of processing a single eRDFa element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcessingResult {
+    /// The element's content is ignored (e.g. `eRDFa:example`).
+    Skip,
+    /// The element's content, unescaped back into RDFa.
+    Extract(String),
+}
+
+impl ProcessingResult {
+    /// Transform the extracted content, leaving `Skip` untouched.
+    pub fn map<F: FnOnce(String) -> String>(self, f: F) -> ProcessingResult {
+        match self {
+            ProcessingResult::Skip => ProcessingResult::Skip,
+            ProcessingResult::Extract(content) => ProcessingResult::Extract(f(content)),
+        }
+    }
+
+    /// Chain a further processing step that may itself skip.
+    pub fn and_then<F: FnOnce(String) -> ProcessingResult>(self, f: F) -> ProcessingResult {
+        match self {
+            ProcessingResult::Skip => ProcessingResult::Skip,
+            ProcessingResult::Extract(content) => f(content),
+        }
+    }
+
+    /// The extracted content, or `None` for `Skip`.
+    pub fn extracted(self) -> Option<String> {
+        match self {
+            ProcessingResult::Skip => None,
+            ProcessingResult::Extract(content) => Some(content),
+        }
+    }
+}
+
+/// Escape RDFa markup for embedding in a platform that strips raw tags.
+pub fn escape(input: &str) -> String {
+    input
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Reverse [`escape`].
+pub fn unescape(input: &str) -> String {
+    input
+        .replace("&quot;", "\"")
+        .replace("&gt;", ">")
+        .replace("&lt;", "<")
+        .replace("&amp;", "&")
+}
+
+/// Single-pass [`escape`]: byte-identical output, but one scan over the
+/// input instead of four sequential `replace` passes, so large
+/// documents are not copied four times.
+pub fn escape_fast(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let mut start = 0;
+    for (i, byte) in input.bytes().enumerate() {
+        let replacement = match byte {
+            b'&' => "&amp;",
+            b'<' => "&lt;",
+            b'>' => "&gt;",
+            b'"' => "&quot;",
+            _ => continue,
+        };
+        out.push_str(&input[start..i]);
+        out.push_str(replacement);
+        start = i + 1;
+    }
+    out.push_str(&input[start..]);
+    out
+}
+
+/// Single-pass [`unescape`], producing the same output as the
+/// sequential version. Unknown entities are copied through unchanged.
+pub fn unescape_fast(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    let bytes = input.as_bytes();
+    let mut start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] != b'&' {
+            i += 1;
+            continue;
+        }
+        let rest = &input[i..];
+        let (unescaped, len) = if rest.starts_with("&amp;") {
+            ('&', 5)
+        } else if rest.starts_with("&lt;") {
+            ('<', 4)
+        } else if rest.starts_with("&gt;") {
+            ('>', 4)
+        } else if rest.starts_with("&quot;") {
+            ('"', 6)
+        } else {
+            i += 1;
+            continue;
+        };
+        out.push_str(&input[start..i]);
+        out.push(unescaped);
+        i += len;
+        start = i;
+    }
+    out.push_str(&input[start..]);
+    out
+}
+
+/// Hook for consumers that want custom handling per term.
+pub trait ERdfaProcessor {
+    /// Process the escaped content of one element tagged with `term`.
+    fn process(&self, term: Term, escaped_content: &str) -> ProcessingResult;
+}
+
+/// Default processor implementing the 1.0 namespace semantics.
+pub struct Processor;
+
+impl ERdfaProcessor for Processor {
+    fn process(&self, term: Term, escaped_content: &str) -> ProcessingResult {
+        match term {
+            Term::Example => ProcessingResult::Skip,
+            Term::Embedded => ProcessingResult::Extract(unescape(escaped_content)),
+        }
+    }
+}
+
+/// Scan an HTML document for `rel="eRDFa:..."` elements and return each
+/// recognised term with its raw (still escaped) content.
+///
+/// Because eRDFa content is escaped, the body of an element contains no
+/// literal `<`; the content therefore runs from the end of the opening
+/// tag to the next `<`.
+pub fn extract_elements(html: &str) -> Vec<(Term, String)> {
+    let mut elements = Vec::new();
+    let mut rest = html;
+    while let Some(pos) = rest.find("rel=\"eRDFa:") {
+        let after = &rest[pos + "rel=\"".len()..];
+        let quote = match after.find('"') {
+            Some(q) => q,
+            None => break,
+        };
+        let term = Term::from_tag(&after[..quote]);
+        let after_tag = &after[quote + 1..];
+        let gt = match after_tag.find('>') {
+            Some(g) => g,
+            None => break,
+        };
+        let body = &after_tag[gt + 1..];
+        let end = body.find('<').unwrap_or(body.len());
+        if let Some(term) = term {
+            elements.push((term, body[..end].to_string()));
+        }
+        rest = &body[end..];
+    }
+    elements
+}
+
+/// Conformance check for custom processors: escapes `content`, wraps
+/// it in an `eRDFa:embedded` element, runs it through `processor`, and
+/// panics unless the extracted result equals the original. Downstream
+/// crates enable the `test-util` feature to use this in their own
+/// test suites.
+///
+/// # Panics
+///
+/// Panics when the processor skips embedded content or extracts
+/// something other than `content`.
+#[cfg(feature = "test-util")]
+pub fn assert_roundtrip(processor: &dyn ERdfaProcessor, content: &str) {
+    let html = format!("<div rel=\"eRDFa:embedded\">{}</div>", escape(content));
+    let elements = extract_elements(&html);
+    assert_eq!(elements.len(), 1, "embedded element not recognised");
+    match processor.process(elements[0].0, &elements[0].1) {
+        ProcessingResult::Extract(extracted) => assert_eq!(
+            extracted, content,
+            "processor did not preserve embedded content"
+        ),
+        ProcessingResult::Skip => panic!("processor skipped embedded content"),
+    }
+}
+
+const ELEMENT_MARKER: &[u8] = b"rel=\"eRDFa:";
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+/// Incrementally scan a reader for eRDFa elements, yielding each as it
+/// completes. Elements may span internal buffer boundaries; memory use
+/// is bounded by the largest single element rather than the document.
+pub fn extract_from_reader<R: std::io::BufRead>(reader: R) -> ExtractElements<R> {
+    ExtractElements {
+        reader,
+        buffer: Vec::new(),
+        eof: false,
+    }
+}
+
+/// Iterator returned by [`extract_from_reader`].
+pub struct ExtractElements<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    eof: bool,
+}
+
+impl<R: std::io::BufRead> ExtractElements<R> {
+    fn refill(&mut self) {
+        match self.reader.fill_buf() {
+            Ok([]) | Err(_) => self.eof = true,
+            Ok(chunk) => {
+                let len = chunk.len();
+                self.buffer.extend_from_slice(chunk);
+                self.reader.consume(len);
+            }
+        }
+    }
+}
+
+impl<R: std::io::BufRead> Iterator for ExtractElements<R> {
+    type Item = (Term, String);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match find_bytes(&self.buffer, ELEMENT_MARKER) {
+                Some(pos) => {
+                    let tag_start = pos + b"rel=\"".len();
+                    // Element layout: tag until `"`, opening tag until
+                    // `>`, escaped content until the next literal `<`.
+                    let parsed = (|| {
+                        let quote = find_bytes(&self.buffer[tag_start..], b"\"")? + tag_start;
+                        let gt = find_bytes(&self.buffer[quote..], b">")? + quote;
+                        let content_start = gt + 1;
+                        let end = match find_bytes(&self.buffer[content_start..], b"<") {
+                            Some(i) => content_start + i,
+                            None if self.eof => self.buffer.len(),
+                            None => return None,
+                        };
+                        Some((quote, content_start, end))
+                    })();
+                    match parsed {
+                        Some((quote, content_start, end)) => {
+                            let term = Term::from_tag(&String::from_utf8_lossy(
+                                &self.buffer[tag_start..quote],
+                            ));
+                            let content = String::from_utf8_lossy(&self.buffer[content_start..end])
+                                .into_owned();
+                            self.buffer.drain(..end);
+                            if let Some(term) = term {
+                                return Some((term, content));
+                            }
+                        }
+                        None if self.eof => return None,
+                        None => self.refill(),
+                    }
+                }
+                None if self.eof => return None,
+                None => {
+                    // Keep only a possible partial marker at the tail.
+                    let keep = ELEMENT_MARKER.len() - 1;
+                    if self.buffer.len() > keep {
+                        let cut = self.buffer.len() - keep;
+                        self.buffer.drain(..cut);
+                    }
+                    self.refill();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_roundtrip() {
+        let rdfa = "<div rel=\"eRDFa:embedded\" prefix=\"a&b\">";
+        assert_eq!(unescape(&escape(rdfa)), rdfa);
+    }
+
+    #[test]
+    fn test_term_all_covers_every_variant() {
+        // The exhaustive match breaks the build if a variant is added
+        // without updating `Term::all`; the length check catches a
+        // variant dropped from, or duplicated in, the list.
+        for term in Term::all() {
+            match term {
+                Term::Example | Term::Embedded => {}
+            }
+        }
+        assert_eq!(Term::all().len(), 2);
+        // Every listed term survives the tag round-trip.
+        for &term in Term::all() {
+            assert_eq!(Term::from_tag(term.tag()), Some(term));
+        }
+    }
+
+    #[test]
+    fn test_fast_paths_match_sequential_escaping() {
+        let inputs = [
+            "",
+            "plain text with no specials",
+            "<div rel=\"eRDFa:embedded\" prefix=\"a&b\">",
+            "&amp;lt; already-escaped &quot;entities&quot;",
+            "&unknown; entity & dangling &am",
+            "unicode résumé <\"&> mixé",
+        ];
+        for input in inputs {
+            assert_eq!(escape_fast(input), escape(input), "escape {:?}", input);
+            assert_eq!(unescape_fast(input), unescape(input), "unescape {:?}", input);
+            let escaped = escape(input);
+            assert_eq!(unescape_fast(&escaped), input, "roundtrip {:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_extract_embedded() {
+        let html = "<div rel=\"eRDFa:embedded\">&lt;p property=&quot;name&quot;&gt;x&lt;/p&gt;</div>";
+        let elements = extract_elements(html);
+        assert_eq!(elements.len(), 1);
+        assert_eq!(elements[0].0, Term::Embedded);
+        match Processor.process(elements[0].0, &elements[0].1) {
+            ProcessingResult::Extract(content) => {
+                assert_eq!(content, "<p property=\"name\">x</p>");
+            }
+            ProcessingResult::Skip => panic!("embedded content must be extracted"),
+        }
+    }
+
+    #[test]
+    fn test_processing_result_map() {
+        let mapped = ProcessingResult::Extract("rdfa".to_string()).map(|c| c.to_uppercase());
+        assert_eq!(mapped, ProcessingResult::Extract("RDFA".to_string()));
+        assert_eq!(
+            ProcessingResult::Skip.map(|c| c.to_uppercase()),
+            ProcessingResult::Skip
+        );
+        assert_eq!(mapped.extracted().as_deref(), Some("RDFA"));
+        assert_eq!(ProcessingResult::Skip.extracted(), None);
+    }
+
+    /// BufRead returning fixed-size chunks, to exercise elements
+    /// spanning buffer boundaries.
+    struct ChunkReader<'a> {
+        data: &'a [u8],
+        pos: usize,
+        chunk: usize,
+    }
+
+    impl std::io::Read for ChunkReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let end = (self.pos + self.chunk.min(buf.len())).min(self.data.len());
+            let len = end - self.pos;
+            buf[..len].copy_from_slice(&self.data[self.pos..end]);
+            self.pos = end;
+            Ok(len)
+        }
+    }
+
+    impl std::io::BufRead for ChunkReader<'_> {
+        fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+            let end = (self.pos + self.chunk).min(self.data.len());
+            Ok(&self.data[self.pos..end])
+        }
+
+        fn consume(&mut self, amt: usize) {
+            self.pos += amt;
+        }
+    }
+
+    #[test]
+    fn test_extract_from_reader_across_chunks() {
+        let html = "<div rel=\"eRDFa:embedded\">&lt;p&gt;one&lt;/p&gt;</div>\n\
+                    <div rel=\"eRDFa:example\">ignored&lt;/div&gt;</div>\n\
+                    <div rel=\"eRDFa:embedded\">&lt;p&gt;two&lt;/p&gt;</div>";
+        let reader = ChunkReader {
+            data: html.as_bytes(),
+            pos: 0,
+            chunk: 7,
+        };
+        let elements: Vec<(Term, String)> = extract_from_reader(reader).collect();
+        assert_eq!(elements.len(), 3);
+        assert_eq!(elements[0], (Term::Embedded, "&lt;p&gt;one&lt;/p&gt;".to_string()));
+        assert_eq!(elements[1].0, Term::Example);
+        assert_eq!(elements[2], (Term::Embedded, "&lt;p&gt;two&lt;/p&gt;".to_string()));
+        // The streaming scan matches the in-memory scan.
+        assert_eq!(elements, extract_elements(html));
+    }
+
+    #[cfg(feature = "test-util")]
+    #[test]
+    fn test_assert_roundtrip_default_processor() {
+        assert_roundtrip(&Processor, "<p property=\"name\">Jim & \"Dupont\"</p>");
+    }
+
+    #[test]
+    fn test_example_is_skipped() {
+        assert_eq!(
+            Processor.process(Term::Example, "anything"),
+            ProcessingResult::Skip
+        );
+    }
+}
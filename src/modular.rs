@@ -0,0 +1,266 @@
+//! The modular theory of knowledge levels.
+//!
+//! Structures are classified by how much of the exceptional ladder they
+//! climb — from finite toy systems, through modular (Leech-sized)
+//! ontologies and Gandalf completeness at 71 symmetries, up to Monster
+//! completeness at the 196,883 fundamental nodes.
+
+use std::collections::HashSet;
+
+use crate::blockchain::SemanticBlockchain;
+
+/// Dimension of the smallest faithful Monster representation.
+pub const FUNDAMENTAL_NODES: u64 = 196_883;
+
+/// The order of the Monster group, saturated: the true order is about
+/// `8 * 10^53`, beyond even `u128`, so it is carried the same way the
+/// catalog's `u64` state counts are — pinned at the type's maximum.
+pub const MONSTER_ORDER: u128 = u128::MAX;
+
+/// Anything that can report the size of its state, dimension, encoding
+/// and symmetry structure.
+pub trait GandalfComplete {
+    fn count_states(&self) -> u64;
+    fn count_dimensions(&self) -> u64;
+    fn count_encodings(&self) -> u64;
+    fn count_symmetries(&self) -> u64;
+
+    /// The state count in `u128`. Structures bigger than `u64` — the
+    /// Monster among them — override this; everything else inherits the
+    /// widened [`count_states`](Self::count_states).
+    fn count_states_u128(&self) -> u128 {
+        self.count_states() as u128
+    }
+}
+
+/// Where a structure sits on the exceptional ladder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum KnowledgeLevel {
+    Finite,
+    Modular,
+    GandalfComplete,
+    MonsterComplete,
+}
+
+/// True once the state count reaches the fundamental node count.
+/// Counts in `u128` so states beyond `u64` qualify rather than wrap.
+pub fn reaches_fundamental_complexity<G: GandalfComplete>(subject: &G) -> bool {
+    subject.count_states_u128() >= FUNDAMENTAL_NODES as u128
+}
+
+/// Classify a structure on the exceptional ladder.
+pub fn knowledge_level<G: GandalfComplete>(subject: &G) -> KnowledgeLevel {
+    if reaches_fundamental_complexity(subject) || subject.count_dimensions() >= FUNDAMENTAL_NODES {
+        KnowledgeLevel::MonsterComplete
+    } else if subject.count_symmetries() >= 71 {
+        KnowledgeLevel::GandalfComplete
+    } else if subject.count_dimensions() >= 24 {
+        KnowledgeLevel::Modular
+    } else {
+        KnowledgeLevel::Finite
+    }
+}
+
+/// One row of the exceptional-ontology catalog: a named structure with
+/// its counting data, so new ontologies are new entries rather than new
+/// types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KnownOntology {
+    pub name: &'static str,
+    pub dimensions: u64,
+    pub symmetries: u64,
+    pub encodings: u64,
+    pub states: u64,
+    /// Full state count for entries whose order exceeds `u64`.
+    pub states_u128: Option<u128>,
+    /// Dimension of the smallest faithful representation.
+    pub rep_dim: u64,
+}
+
+/// The catalog: Fano plane, octonions, Leech lattice and the Monster.
+/// The Monster's state count saturates both widths; `states_u128` still
+/// lifts it far past every `u64`-sized entry.
+const KNOWN_ONTOLOGIES: [KnownOntology; 4] = [
+    KnownOntology {
+        name: "fano",
+        dimensions: 3,
+        symmetries: 168,
+        encodings: 7,
+        states: 7,
+        states_u128: None,
+        rep_dim: 3,
+    },
+    KnownOntology {
+        name: "octonion",
+        dimensions: 8,
+        symmetries: 14,
+        encodings: 480,
+        states: 8,
+        states_u128: None,
+        rep_dim: 7,
+    },
+    KnownOntology {
+        name: "leech",
+        dimensions: 24,
+        symmetries: 8_315_553_613_086_720_000,
+        encodings: 24,
+        states: 196_560,
+        states_u128: None,
+        rep_dim: 24,
+    },
+    KnownOntology {
+        name: "monster",
+        dimensions: FUNDAMENTAL_NODES,
+        symmetries: 71,
+        encodings: 194,
+        states: u64::MAX,
+        states_u128: Some(MONSTER_ORDER),
+        rep_dim: FUNDAMENTAL_NODES,
+    },
+];
+
+/// Every ontology the crate knows about, in ascending order of size.
+pub fn known_ontologies() -> &'static [KnownOntology] {
+    &KNOWN_ONTOLOGIES
+}
+
+impl GandalfComplete for KnownOntology {
+    fn count_states(&self) -> u64 {
+        self.states
+    }
+    fn count_dimensions(&self) -> u64 {
+        self.dimensions
+    }
+    fn count_encodings(&self) -> u64 {
+        self.encodings
+    }
+    fn count_symmetries(&self) -> u64 {
+        self.symmetries
+    }
+    fn count_states_u128(&self) -> u128 {
+        self.states_u128.unwrap_or(self.states as u128)
+    }
+}
+
+/// Distinct values of an RDFa attribute across all mined transactions.
+fn distinct_attribute_values(chain: &SemanticBlockchain, attribute: &str) -> usize {
+    let marker = format!("{}=\"", attribute);
+    let mut values = HashSet::new();
+    for block in &chain.chain {
+        for tx in &block.transactions {
+            let text = String::from_utf8_lossy(&tx.rdfa_data).into_owned();
+            let mut rest = text.as_str();
+            while let Some(pos) = rest.find(&marker) {
+                let after = &rest[pos + marker.len()..];
+                match after.find('"') {
+                    Some(end) => {
+                        values.insert(after[..end].to_string());
+                        rest = &after[end + 1..];
+                    }
+                    None => break,
+                }
+            }
+        }
+    }
+    values.len()
+}
+
+/// The blockchain is itself a knowledge structure: transactions are its
+/// states, distinct predicates its dimensions, distinct subjects its
+/// encodings, and distinct miners its symmetries.
+impl GandalfComplete for SemanticBlockchain {
+    fn count_states(&self) -> u64 {
+        self.get_transaction_count() as u64
+    }
+
+    fn count_dimensions(&self) -> u64 {
+        distinct_attribute_values(self, "property") as u64
+    }
+
+    fn count_encodings(&self) -> u64 {
+        distinct_attribute_values(self, "about") as u64
+    }
+
+    fn count_symmetries(&self) -> u64 {
+        let miners: HashSet<&[u8]> = self
+            .chain
+            .iter()
+            .skip(1)
+            .map(|block| block.miner_address.as_slice())
+            .collect();
+        miners.len() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::ExtractionWitness;
+    use crate::blockchain::SemanticTransaction;
+
+    #[test]
+    fn test_catalog_classifications() {
+        for ontology in known_ontologies() {
+            let expected = match ontology.name {
+                "octonion" => KnowledgeLevel::Finite,
+                // 168 Fano symmetries already clear the Gandalf 71.
+                "fano" | "leech" => KnowledgeLevel::GandalfComplete,
+                "monster" => KnowledgeLevel::MonsterComplete,
+                other => panic!("unclassified catalog entry {}", other),
+            };
+            assert_eq!(knowledge_level(ontology), expected, "{}", ontology.name);
+        }
+    }
+
+    #[test]
+    fn test_u128_state_count_reaches_monster_complete() {
+        // A structure tiny in every u64-visible way, but with a state
+        // count only expressible in u128.
+        struct VastOntology;
+        impl GandalfComplete for VastOntology {
+            fn count_states(&self) -> u64 {
+                0
+            }
+            fn count_dimensions(&self) -> u64 {
+                1
+            }
+            fn count_encodings(&self) -> u64 {
+                1
+            }
+            fn count_symmetries(&self) -> u64 {
+                1
+            }
+            fn count_states_u128(&self) -> u128 {
+                MONSTER_ORDER / 71
+            }
+        }
+        assert!(reaches_fundamental_complexity(&VastOntology));
+        // State count alone lifts it, with no help from dimensions.
+        assert_eq!(knowledge_level(&VastOntology), KnowledgeLevel::MonsterComplete);
+    }
+
+    #[test]
+    fn test_blockchain_classification_grows() {
+        let mut chain = SemanticBlockchain::new();
+        assert_eq!(knowledge_level(&chain), KnowledgeLevel::Finite);
+        for i in 0..24 {
+            let rdfa_data = format!("<div property=\"p{}\" about=\"#s{}\">v</div>", i, i).into_bytes();
+            let witness = ExtractionWitness::generate(&rdfa_data, vec![0]);
+            let fee = 100 + rdfa_data.len() as u64;
+            assert!(chain.add_transaction(SemanticTransaction {
+                rdfa_data,
+                fee,
+                timestamp: i,
+                signature: vec![1],
+                witness,
+                depends_on: Vec::new(),
+            }));
+        }
+        chain.mine_block(b"miner".to_vec(), 100);
+        assert_eq!(chain.count_states(), 24);
+        assert_eq!(chain.count_dimensions(), 24);
+        assert_eq!(chain.count_encodings(), 24);
+        // 24 distinct predicates lift the chain to Modular.
+        assert_eq!(knowledge_level(&chain), KnowledgeLevel::Modular);
+    }
+}
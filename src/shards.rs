@@ -0,0 +1,1098 @@
+//! Document sharding across coin holders.
+//!
+//! An eRDFa document can be split into shards held by the top holders of
+//! a coin, so that reconstructing it requires cooperation of a quorum.
+//! Shard counts follow the exceptional structures of the modular theory
+//! (Fano plane, octonions, Leech lattice, the Gandalf 71, the Monster).
+
+use std::time::Instant;
+
+#[cfg(feature = "zeroize")]
+use zeroize::{Zeroize, Zeroizing};
+
+/// Exceptional structure dictating how many shards a document splits into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DataType {
+    /// Triality: 3 shards.
+    Triad,
+    /// Fano plane points: 7 shards.
+    Fano,
+    /// Octonion units: 8 shards.
+    Octonion,
+    /// Leech lattice dimensions: 24 shards.
+    Leech,
+    /// The 71 of the Gandalf conjecture: 71 shards.
+    Gandalf,
+    /// Smallest faithful Monster representation: 196,883 shards.
+    Monster,
+    /// A caller-chosen shard count; validated when a
+    /// [`ShardingSystem`] is constructed.
+    Custom(usize),
+}
+
+impl DataType {
+    pub fn shard_count(&self) -> usize {
+        match self {
+            DataType::Triad => 3,
+            DataType::Fano => 7,
+            DataType::Octonion => 8,
+            DataType::Leech => 24,
+            DataType::Gandalf => 71,
+            DataType::Monster => 196_883,
+            DataType::Custom(n) => *n,
+        }
+    }
+
+    /// Recover the data type from a stored shard count, e.g. when
+    /// deserializing a shard that only kept the number. Every
+    /// predefined variant's count is distinct, so this is a clean
+    /// inverse of [`shard_count`](Self::shard_count); counts that match
+    /// no predefined variant return `None` rather than guessing
+    /// `Custom`.
+    pub fn from_shard_count(n: usize) -> Option<DataType> {
+        match n {
+            3 => Some(DataType::Triad),
+            7 => Some(DataType::Fano),
+            8 => Some(DataType::Octonion),
+            24 => Some(DataType::Leech),
+            71 => Some(DataType::Gandalf),
+            196_883 => Some(DataType::Monster),
+            _ => None,
+        }
+    }
+}
+
+/// Largest shard count [`ShardingSystem::shard_document`] will
+/// materialize eagerly. Everything up to [`DataType::Gandalf`] fits
+/// comfortably; [`DataType::Monster`]'s 196,883 shards would occupy
+/// roughly 200,000 copies of the document and must be produced lazily
+/// via [`ShardingSystem::shard_document_streaming`].
+pub const MAX_EAGER_SHARDS: usize = 4096;
+
+/// Largest shard count a [`DataType::Custom`] may request; nothing
+/// should out-shard the Monster.
+pub const MAX_CUSTOM_SHARDS: usize = 196_883;
+
+/// Errors splitting a document into shards.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShardError {
+    /// The data type's shard count exceeds [`MAX_EAGER_SHARDS`]; use
+    /// the streaming generator instead.
+    TooManyShards { requested: usize, max: usize },
+    /// A reshare asked for a threshold below the caller's policy
+    /// minimum (e.g. dropping the Gandalf 71-of-71 requirement).
+    ThresholdBelowPolicy { requested: usize, min: usize },
+    /// A reshare threshold must be between 1 and the shard count.
+    InvalidThreshold { requested: usize, total: usize },
+    /// A custom shard count must be between 2 (no sharing happens
+    /// below that) and the configured maximum.
+    InvalidShardCount { requested: usize, max: usize },
+    /// A registry merge mixed coin types without opting in.
+    CoinTypeMismatch { ours: CoinType, theirs: CoinType },
+}
+
+/// The coin whose holders custody the shards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CoinType {
+    ERdfa,
+    Semantic,
+    Gandalf,
+}
+
+/// Multiply in GF(2^8) modulo the AES polynomial x^8+x^4+x^3+x+1.
+pub(crate) fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    while b != 0 {
+        if b & 1 == 1 {
+            product ^= a;
+        }
+        let carry = a & 0x80;
+        a <<= 1;
+        if carry != 0 {
+            a ^= 0x1b;
+        }
+        b >>= 1;
+    }
+    product
+}
+
+/// Multiplicative inverse in GF(2^8) via a^254.
+pub(crate) fn gf_inv(a: u8) -> u8 {
+    let mut result = 1u8;
+    let mut base = a;
+    let mut exp = 254u8;
+    while exp != 0 {
+        if exp & 1 == 1 {
+            result = gf_mul(result, base);
+        }
+        base = gf_mul(base, base);
+        exp >>= 1;
+    }
+    result
+}
+
+/// One Shamir share: the evaluation index `x` and the per-byte
+/// evaluations `y`. Carrying the index makes shares reorderable and
+/// lets reconstruction accept arbitrary subsets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Share {
+    pub x: u8,
+    pub y: Vec<u8>,
+}
+
+/// Threshold secret sharing over document bytes in GF(2^8).
+///
+/// Each secret byte is the constant term of a degree `threshold - 1`
+/// polynomial; share `index` is the polynomial evaluated at
+/// `x = index % 255 + 1`. Coefficients are derived deterministically
+/// from the secret, so splitting the same secret twice yields the same
+/// shares (convergent sharing) and per-holder coordinates stay stable.
+pub struct ShamirSharing {
+    pub threshold: usize,
+    pub total_shares: usize,
+}
+
+impl ShamirSharing {
+    pub fn new(threshold: usize, total_shares: usize) -> Self {
+        ShamirSharing {
+            threshold,
+            total_shares,
+        }
+    }
+
+    /// The evaluation point for a share index; never zero, since x = 0
+    /// holds the secret itself.
+    fn x_coordinate(index: usize) -> u8 {
+        (index % 255) as u8 + 1
+    }
+
+    /// Deterministic coefficient for secret position `pos`, degree `deg`.
+    fn coefficient(&self, secret: &[u8], pos: usize, deg: usize) -> u8 {
+        let mut state = 0x9e37_79b9_7f4a_7c15u64
+            ^ (pos as u64).wrapping_mul(0x1000_0000_01b3)
+            ^ (deg as u64).wrapping_mul(0xa076_1d64_78bd_642f);
+        for &byte in secret {
+            state = (state ^ byte as u64).wrapping_mul(0x100_0000_01b3);
+        }
+        // xorshift finalizer so adjacent positions decorrelate.
+        state ^= state >> 33;
+        state = state.wrapping_mul(0xff51_afd7_ed55_8ccd);
+        state ^= state >> 33;
+        (state & 0xff) as u8
+    }
+
+    pub fn split(&self, secret: &[u8]) -> Vec<Share> {
+        (0..self.total_shares)
+            .map(|index| Share {
+                x: index as u8,
+                y: self.generate_share(secret, index),
+            })
+            .collect()
+    }
+
+    /// Split the secret producing one share per caller-supplied
+    /// x-coordinate, so a holder whose coordinate is stable (e.g.
+    /// derived from their address) receives the same share position
+    /// across re-shards of the same secret.
+    pub fn split_for_holders(&self, secret: &[u8], x_coords: &[u8]) -> Vec<Share> {
+        x_coords
+            .iter()
+            .map(|&x| Share {
+                x,
+                y: self.generate_share(secret, x as usize),
+            })
+            .collect()
+    }
+
+    pub(crate) fn generate_share(&self, secret: &[u8], index: usize) -> Vec<u8> {
+        let x = Self::x_coordinate(index);
+        secret
+            .iter()
+            .enumerate()
+            .map(|(pos, &byte)| {
+                // Horner evaluation of the per-byte polynomial at x.
+                let mut y = 0u8;
+                for deg in (1..self.threshold).rev() {
+                    y = gf_mul(y ^ self.coefficient(secret, pos, deg), x);
+                }
+                y ^ byte
+            })
+            .collect()
+    }
+
+    /// Reconstruct from any `threshold` shares, in any order, via
+    /// Lagrange interpolation at x = 0. Each [`Share`] carries the
+    /// index it was generated at, so arbitrary subsets work.
+    pub fn reconstruct(&self, shares: &[Share]) -> Option<Vec<u8>> {
+        if shares.len() < self.threshold || shares.iter().any(|share| share.y.is_empty()) {
+            return None;
+        }
+        let shares = &shares[..self.threshold];
+        let len = shares[0].y.len();
+        if shares.iter().any(|share| share.y.len() != len) {
+            return None;
+        }
+        let xs: Vec<u8> = shares
+            .iter()
+            .map(|share| Self::x_coordinate(share.x as usize))
+            .collect();
+        let mut secret = vec![0u8; len];
+        for (i, share) in shares.iter().enumerate() {
+            // Lagrange basis value at x = 0 for point i.
+            let mut basis = 1u8;
+            for (j, &xj) in xs.iter().enumerate() {
+                if j != i {
+                    let denominator = xj ^ xs[i];
+                    if denominator == 0 {
+                        return None;
+                    }
+                    basis = gf_mul(basis, gf_mul(xj, gf_inv(denominator)));
+                }
+            }
+            for (pos, &y) in share.y.iter().enumerate() {
+                secret[pos] ^= gf_mul(y, basis);
+            }
+        }
+        Some(secret)
+    }
+
+    /// [`reconstruct`](Self::reconstruct) with the secret handed back
+    /// in a [`Zeroizing`] wrapper, so it is wiped from memory when
+    /// dropped. The share buffers belong to the caller and are left
+    /// intact; wipe them with [`Zeroize::zeroize`] once done with them.
+    #[cfg(feature = "zeroize")]
+    pub fn reconstruct_zeroizing(&self, shares: &[Share]) -> Option<Zeroizing<Vec<u8>>> {
+        self.reconstruct(shares).map(Zeroizing::new)
+    }
+}
+
+/// One shard of a document, custodied by a coin holder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentShard {
+    pub shard_id: usize,
+    pub data: Vec<u8>,
+    pub holder_address: Vec<u8>,
+    pub block_height: u64,
+    pub signature: Vec<u8>,
+}
+
+/// A document split into shards, keyed by its content hash.
+#[derive(Debug, Clone)]
+pub struct ShardedDocument {
+    pub document_id: [u8; 32],
+    pub shards: Vec<DocumentShard>,
+    pub total_shards: usize,
+    pub required_shards: usize,
+}
+
+/// Proof that a holder custodies one shard of a document. It carries a
+/// commitment over the shard bytes rather than the bytes themselves, so
+/// a holder can claim a reward without revealing shard `data`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HoldingProof {
+    pub document_id: [u8; 32],
+    pub shard_id: usize,
+    pub holder_address: Vec<u8>,
+    pub commitment: [u8; 32],
+}
+
+impl ShardedDocument {
+    /// Commitment binding a shard's bytes to this document and holder.
+    fn holding_commitment(&self, shard: &DocumentShard, holder_address: &[u8]) -> [u8; 32] {
+        let mut material = Vec::with_capacity(32 + shard.data.len() + holder_address.len());
+        material.extend_from_slice(&self.document_id);
+        material.extend_from_slice(&shard.data);
+        material.extend_from_slice(holder_address);
+        hash_document(&material)
+    }
+
+    /// Build a holding proof for the shard with `shard_id`, or `None`
+    /// if no such shard is custodied by `holder_key`.
+    pub fn prove_holding(&self, shard_id: usize, holder_key: &[u8]) -> Option<HoldingProof> {
+        let shard = self
+            .shards
+            .iter()
+            .find(|shard| shard.shard_id == shard_id && shard.holder_address == holder_key)?;
+        Some(HoldingProof {
+            document_id: self.document_id,
+            shard_id,
+            holder_address: shard.holder_address.clone(),
+            commitment: self.holding_commitment(shard, holder_key),
+        })
+    }
+
+    /// Check a holding proof against this document: the verifier
+    /// recomputes the commitment from its own copy of the shard, so the
+    /// prover never has to transmit shard bytes.
+    pub fn verify_holding(&self, proof: &HoldingProof) -> bool {
+        if proof.document_id != self.document_id {
+            return false;
+        }
+        match self.shards.iter().find(|shard| shard.shard_id == proof.shard_id) {
+            Some(shard) => {
+                shard.holder_address == proof.holder_address
+                    && self.holding_commitment(shard, &proof.holder_address) == proof.commitment
+            }
+            None => false,
+        }
+    }
+}
+
+/// A coin holder's balance snapshot at a block height.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoinHolder {
+    pub address: Vec<u8>,
+    pub balance: u64,
+    pub block_height: u64,
+}
+
+/// Registry of holder balance snapshots for one coin.
+pub struct CoinHolderRegistry {
+    pub coin_type: CoinType,
+    holders: Vec<CoinHolder>,
+}
+
+impl CoinHolderRegistry {
+    pub fn new(coin_type: CoinType) -> Self {
+        CoinHolderRegistry {
+            coin_type,
+            holders: Vec::new(),
+        }
+    }
+
+    pub fn register_holder(&mut self, address: Vec<u8>, balance: u64, block_height: u64) {
+        self.holders.push(CoinHolder {
+            address,
+            balance,
+            block_height,
+        });
+    }
+
+    /// Rank holders by balance and return the top `n` as of a block.
+    /// Only snapshots taken at or before `block_height` participate;
+    /// later snapshots did not exist yet at that block.
+    pub fn get_top_n_at_block(&self, n: usize, block_height: u64) -> Vec<CoinHolder> {
+        let mut ranked: Vec<CoinHolder> = self
+            .holders
+            .iter()
+            .filter(|holder| holder.block_height <= block_height)
+            .cloned()
+            .collect();
+        ranked.sort_by_key(|holder| std::cmp::Reverse(holder.balance));
+        ranked.truncate(n);
+        ranked
+    }
+
+    /// The holder's most recent snapshot at or before `block_height` —
+    /// the balance a verifier at that block would have seen.
+    pub fn holder_at_block(&self, address: &[u8], block_height: u64) -> Option<&CoinHolder> {
+        self.holders
+            .iter()
+            .filter(|h| h.address == address && h.block_height <= block_height)
+            .max_by_key(|h| h.block_height)
+    }
+
+    pub fn verify_holder_at_block(&self, address: &[u8], block_height: u64) -> bool {
+        self.holder_at_block(address, block_height).is_some()
+    }
+
+    /// Fold `other`'s snapshots into this registry, so rankings like
+    /// [`get_top_n_at_block`](Self::get_top_n_at_block) run over the
+    /// union. Snapshots sharing an address and block height combine by
+    /// summing balances; distinct snapshots are kept as-is. Refuses a
+    /// registry for a different coin — use
+    /// [`merge_cross_coin`](Self::merge_cross_coin) to combine
+    /// holdings across coins deliberately.
+    pub fn merge(&mut self, other: &CoinHolderRegistry) -> Result<(), ShardError> {
+        if other.coin_type != self.coin_type {
+            return Err(ShardError::CoinTypeMismatch {
+                ours: self.coin_type,
+                theirs: other.coin_type,
+            });
+        }
+        self.merge_unchecked(other);
+        Ok(())
+    }
+
+    /// [`merge`](Self::merge) without the coin-type guard, for callers
+    /// that explicitly want one ranking across several coins.
+    pub fn merge_cross_coin(&mut self, other: &CoinHolderRegistry) {
+        self.merge_unchecked(other);
+    }
+
+    fn merge_unchecked(&mut self, other: &CoinHolderRegistry) {
+        for snapshot in &other.holders {
+            let existing = self.holders.iter_mut().find(|holder| {
+                holder.address == snapshot.address && holder.block_height == snapshot.block_height
+            });
+            match existing {
+                Some(holder) => holder.balance += snapshot.balance,
+                None => self.holders.push(snapshot.clone()),
+            }
+        }
+    }
+}
+
+/// Hash a document into a 32-byte identifier. A rolling FNV-style
+/// state feeds each lane, so unlike a plain XOR fold the result
+/// depends on byte order and not just byte content.
+pub fn hash_document(document: &[u8]) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    let mut state = 0xcbf2_9ce4_8422_2325u64 ^ document.len() as u64;
+    for (i, &byte) in document.iter().enumerate() {
+        state = (state ^ byte as u64).wrapping_mul(0x100_0000_01b3);
+        // Fold the low byte in as well: late-round differences live in
+        // the low bits and would vanish from bits 24..32 alone.
+        hash[i % 32] ^= (state ^ (state >> 24)) as u8;
+    }
+    hash
+}
+
+/// Splits documents into shards and reassembles them from a quorum.
+pub struct ShardingSystem {
+    pub data_type: DataType,
+    pub shamir: ShamirSharing,
+    pub registry: CoinHolderRegistry,
+}
+
+impl ShardingSystem {
+    /// An all-shards-required system: `threshold == shard count`.
+    /// Custom shard counts are validated against [`MAX_CUSTOM_SHARDS`].
+    pub fn new(data_type: DataType, coin_type: CoinType) -> Result<Self, ShardError> {
+        Self::with_threshold(data_type, coin_type, data_type.shard_count())
+    }
+
+    /// Like [`new`](Self::new) with a caller-chosen cap on custom
+    /// shard counts.
+    pub fn new_with_custom_limit(
+        data_type: DataType,
+        coin_type: CoinType,
+        max_custom: usize,
+    ) -> Result<Self, ShardError> {
+        if let DataType::Custom(n) = data_type {
+            if n < 2 || n > max_custom {
+                return Err(ShardError::InvalidShardCount {
+                    requested: n,
+                    max: max_custom,
+                });
+            }
+        }
+        Ok(ShardingSystem {
+            data_type,
+            shamir: ShamirSharing::new(data_type.shard_count(), data_type.shard_count()),
+            registry: CoinHolderRegistry::new(coin_type),
+        })
+    }
+
+    /// A real (k, n) system: any `threshold` of the type's shards
+    /// reconstruct the document.
+    pub fn with_threshold(
+        data_type: DataType,
+        coin_type: CoinType,
+        threshold: usize,
+    ) -> Result<Self, ShardError> {
+        let mut system = Self::new_with_custom_limit(data_type, coin_type, MAX_CUSTOM_SHARDS)?;
+        system.shamir = ShamirSharing::new(threshold, data_type.shard_count());
+        Ok(system)
+    }
+
+    pub fn shard_document(
+        &mut self,
+        document: &[u8],
+        block_height: u64,
+    ) -> Result<ShardedDocument, ShardError> {
+        self.shard_document_with_progress(document, block_height, &mut |_, _| {})
+    }
+
+    /// Like [`shard_document`](Self::shard_document), invoking `progress`
+    /// with `(shards_completed, total)` after each shard is produced, so
+    /// long-running splits (Gandalf and above) can report progress.
+    pub fn shard_document_with_progress(
+        &mut self,
+        document: &[u8],
+        block_height: u64,
+        progress: &mut dyn FnMut(usize, usize),
+    ) -> Result<ShardedDocument, ShardError> {
+        let total = self.data_type.shard_count();
+        if total > MAX_EAGER_SHARDS {
+            return Err(ShardError::TooManyShards {
+                requested: total,
+                max: MAX_EAGER_SHARDS,
+            });
+        }
+        let mut shards = Vec::with_capacity(total);
+        for shard_id in 0..total {
+            let data = self.shamir.generate_share(document, shard_id);
+            shards.push(DocumentShard {
+                shard_id,
+                data,
+                holder_address: Vec::new(),
+                block_height,
+                signature: Vec::new(),
+            });
+            progress(shard_id + 1, total);
+        }
+        Ok(ShardedDocument {
+            document_id: hash_document(document),
+            shards,
+            total_shards: total,
+            required_shards: self.shamir.threshold,
+        })
+    }
+
+    /// Adopt a new reconstruction threshold for future shardings.
+    ///
+    /// `min_threshold` is the caller's security policy floor: a reshare
+    /// that would weaken reconstruction below it is refused, so a
+    /// fat-fingered downgrade of, say, the Gandalf 71 requirement fails
+    /// loudly instead of silently resharding at the lower quorum.
+    pub fn reshare(
+        &mut self,
+        new_threshold: usize,
+        min_threshold: usize,
+    ) -> Result<(), ShardError> {
+        let total = self.data_type.shard_count();
+        if new_threshold == 0 || new_threshold > total {
+            return Err(ShardError::InvalidThreshold {
+                requested: new_threshold,
+                total,
+            });
+        }
+        if new_threshold < min_threshold {
+            return Err(ShardError::ThresholdBelowPolicy {
+                requested: new_threshold,
+                min: min_threshold,
+            });
+        }
+        self.shamir = ShamirSharing::new(new_threshold, total);
+        Ok(())
+    }
+
+    /// Produce shards lazily, one per `next` call, so shard counts
+    /// beyond [`MAX_EAGER_SHARDS`] (i.e. Monster) never hold more than
+    /// one shard in memory at a time.
+    pub fn shard_document_streaming<'a>(
+        &'a self,
+        document: &'a [u8],
+        block_height: u64,
+    ) -> ShardStream<'a> {
+        ShardStream {
+            shamir: &self.shamir,
+            document,
+            block_height,
+            next_id: 0,
+            total: self.data_type.shard_count(),
+        }
+    }
+
+    /// Sign a shard so [`verify_signature`](Self::verify_signature)
+    /// accepts it. The toy XOR scheme is symmetric: verification uses
+    /// the same key material that signed.
+    pub fn sign_shard(&self, shard: &mut DocumentShard, private_key: &[u8]) {
+        let data_fold = shard.data.iter().fold(0u8, |acc, &b| acc ^ b);
+        let key_fold = private_key.iter().fold(0u8, |acc, &b| acc ^ b);
+        shard.signature = vec![data_fold ^ key_fold];
+    }
+
+    pub fn verify_signature(&self, shard: &DocumentShard, public_key: &[u8]) -> bool {
+        if shard.signature.is_empty() {
+            return false;
+        }
+        let data_fold = shard.data.iter().fold(0u8, |acc, &b| acc ^ b);
+        let sig_fold = shard.signature.iter().fold(0u8, |acc, &b| acc ^ b);
+        let key_fold = public_key.iter().fold(0u8, |acc, &b| acc ^ b);
+        sig_fold == data_fold ^ key_fold
+    }
+
+    /// Verify both the shard signature and that the signing holder was
+    /// registered at the shard's block height.
+    pub fn verify_shard_signature(&self, shard: &DocumentShard, public_key: &[u8]) -> bool {
+        self.registry
+            .verify_holder_at_block(&shard.holder_address, shard.block_height)
+            && self.verify_signature(shard, public_key)
+    }
+
+    pub fn reconstruct_document(
+        &self,
+        sharded: &ShardedDocument,
+        public_key: &[u8],
+    ) -> Option<Vec<u8>> {
+        self.reconstruct_document_instrumented(sharded, public_key).0
+    }
+
+    /// [`reconstruct_document`](Self::reconstruct_document) plus a
+    /// timing and shard-count breakdown. Stats come back whether or not
+    /// reconstruction succeeds, so a failed run can still show whether
+    /// verification or interpolation dominated. At the Gandalf 71 and
+    /// beyond this is the difference between tuning the threshold and
+    /// guessing.
+    pub fn reconstruct_document_instrumented(
+        &self,
+        sharded: &ShardedDocument,
+        public_key: &[u8],
+    ) -> (Option<Vec<u8>>, ReconstructionStats) {
+        let verify_start = Instant::now();
+        let valid: Vec<Share> = sharded
+            .shards
+            .iter()
+            .filter(|shard| self.verify_signature(shard, public_key))
+            .map(|shard| Share {
+                x: shard.shard_id as u8,
+                y: shard.data.clone(),
+            })
+            .collect();
+        let mut stats = ReconstructionStats {
+            verified_shards: valid.len(),
+            failed_shards: sharded.shards.len() - valid.len(),
+            verify_time_us: verify_start.elapsed().as_micros(),
+            interpolate_time_us: 0,
+        };
+        if valid.len() < sharded.required_shards {
+            return (None, stats);
+        }
+        let interpolate_start = Instant::now();
+        let reconstructed = self.shamir.reconstruct(&valid);
+        stats.interpolate_time_us = interpolate_start.elapsed().as_micros();
+        let reconstructed = match reconstructed {
+            Some(bytes) => bytes,
+            None => return (None, stats),
+        };
+        // A quorum of individually valid shares can still interpolate
+        // to the wrong bytes (e.g. consistent tampering); the document
+        // id is the final arbiter.
+        if hash_document(&reconstructed) != sharded.document_id {
+            return (None, stats);
+        }
+        (Some(reconstructed), stats)
+    }
+
+    /// [`reconstruct_document`](Self::reconstruct_document) for
+    /// security-sensitive callers: the intermediate share copies are
+    /// zeroized before returning, and the document comes back in a
+    /// [`Zeroizing`] wrapper that wipes it on drop.
+    #[cfg(feature = "zeroize")]
+    pub fn reconstruct_document_zeroizing(
+        &self,
+        sharded: &ShardedDocument,
+        public_key: &[u8],
+    ) -> Option<Zeroizing<Vec<u8>>> {
+        let mut valid: Vec<Share> = sharded
+            .shards
+            .iter()
+            .filter(|shard| self.verify_signature(shard, public_key))
+            .map(|shard| Share {
+                x: shard.shard_id as u8,
+                y: shard.data.clone(),
+            })
+            .collect();
+        let reconstructed = if valid.len() < sharded.required_shards {
+            None
+        } else {
+            self.shamir
+                .reconstruct(&valid)
+                .filter(|document| hash_document(document) == sharded.document_id)
+        };
+        for share in &mut valid {
+            share.y.zeroize();
+        }
+        reconstructed.map(Zeroizing::new)
+    }
+}
+
+/// Where reconstruction spent its time, from
+/// [`ShardingSystem::reconstruct_document_instrumented`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReconstructionStats {
+    /// Shards whose signature verified.
+    pub verified_shards: usize,
+    /// Shards rejected during verification.
+    pub failed_shards: usize,
+    /// Microseconds spent verifying signatures.
+    pub verify_time_us: u128,
+    /// Microseconds spent interpolating; zero when verification already
+    /// left the quorum short.
+    pub interpolate_time_us: u128,
+}
+
+/// Lazy shard generator returned by
+/// [`ShardingSystem::shard_document_streaming`].
+pub struct ShardStream<'a> {
+    shamir: &'a ShamirSharing,
+    document: &'a [u8],
+    block_height: u64,
+    next_id: usize,
+    total: usize,
+}
+
+impl Iterator for ShardStream<'_> {
+    type Item = DocumentShard;
+
+    fn next(&mut self) -> Option<DocumentShard> {
+        if self.next_id >= self.total {
+            return None;
+        }
+        let shard_id = self.next_id;
+        self.next_id += 1;
+        Some(DocumentShard {
+            shard_id,
+            data: self.shamir.generate_share(self.document, shard_id),
+            holder_address: Vec::new(),
+            block_height: self.block_height,
+            signature: Vec::new(),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.total - self.next_id;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for ShardStream<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shamir_sharing() {
+        let shamir = ShamirSharing::new(3, 5);
+        let shares = shamir.split(b"Secret message");
+        assert_eq!(shares.len(), 5);
+        // Shares must not leak the secret directly.
+        assert!(shares.iter().all(|s| s.y != b"Secret message"));
+        let reconstructed = shamir.reconstruct(&shares).expect("enough shares");
+        assert_eq!(reconstructed, b"Secret message");
+    }
+
+    #[test]
+    fn test_shamir_every_three_subset_reconstructs() {
+        let shamir = ShamirSharing::new(3, 5);
+        let shares = shamir.split(b"Secret message");
+        for a in 0..5 {
+            for b in (a + 1)..5 {
+                for c in (b + 1)..5 {
+                    let subset = vec![shares[a].clone(), shares[c].clone(), shares[b].clone()];
+                    assert_eq!(
+                        shamir.reconstruct(&subset).as_deref(),
+                        Some(b"Secret message".as_slice()),
+                        "subset ({}, {}, {}) failed",
+                        a,
+                        b,
+                        c
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_reconstruct_from_shards_zero_two_four() {
+        let shamir = ShamirSharing::new(3, 5);
+        let shares = shamir.split(b"Secret message");
+        let subset = vec![shares[0].clone(), shares[2].clone(), shares[4].clone()];
+        assert_eq!(
+            shamir.reconstruct(&subset).as_deref(),
+            Some(b"Secret message".as_slice())
+        );
+    }
+
+    #[test]
+    fn test_split_for_holders_is_stable_per_coordinate() {
+        let shamir = ShamirSharing::new(3, 5);
+        let first = shamir.split_for_holders(b"Secret message", &[11, 42, 97]);
+        let second = shamir.split_for_holders(b"Secret message", &[42]);
+        // Holder at x = 42 receives the same share both times.
+        assert_eq!(first[1], second[0]);
+    }
+
+    #[test]
+    fn test_from_shard_count_inverts_shard_count() {
+        let all = [
+            DataType::Triad,
+            DataType::Fano,
+            DataType::Octonion,
+            DataType::Leech,
+            DataType::Gandalf,
+            DataType::Monster,
+        ];
+        for data_type in all {
+            assert_eq!(
+                DataType::from_shard_count(data_type.shard_count()),
+                Some(data_type)
+            );
+        }
+        assert_eq!(DataType::from_shard_count(0), None);
+        assert_eq!(DataType::from_shard_count(9), None);
+    }
+
+    #[test]
+    fn test_top_n_excludes_future_snapshots() {
+        let mut registry = CoinHolderRegistry::new(CoinType::ERdfa);
+        registry.register_holder(b"early-rich".to_vec(), 500, 100);
+        registry.register_holder(b"early-poor".to_vec(), 50, 100);
+        registry.register_holder(b"late-whale".to_vec(), 9_000, 200);
+        let top = registry.get_top_n_at_block(2, 150);
+        // The block-200 snapshot did not exist at block 150.
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].address, b"early-rich");
+        assert_eq!(top[0].block_height, 100);
+        assert_eq!(top[1].address, b"early-poor");
+    }
+
+    #[test]
+    fn test_merge_ranks_across_combined_registries() {
+        let mut ours = CoinHolderRegistry::new(CoinType::ERdfa);
+        ours.register_holder(b"alice".to_vec(), 500, 100);
+        ours.register_holder(b"bob".to_vec(), 200, 100);
+        let mut theirs = CoinHolderRegistry::new(CoinType::ERdfa);
+        theirs.register_holder(b"carol".to_vec(), 900, 100);
+        // Same holder, same snapshot height: balances combine.
+        theirs.register_holder(b"bob".to_vec(), 450, 100);
+        assert_eq!(ours.merge(&theirs), Ok(()));
+        let top = ours.get_top_n_at_block(3, 100);
+        assert_eq!(top[0].address, b"carol");
+        assert_eq!(top[1].address, b"bob");
+        assert_eq!(top[1].balance, 650);
+        assert_eq!(top[2].address, b"alice");
+        // A different coin is refused unless merged deliberately.
+        let gandalf = CoinHolderRegistry::new(CoinType::Gandalf);
+        assert_eq!(
+            ours.merge(&gandalf),
+            Err(ShardError::CoinTypeMismatch {
+                ours: CoinType::ERdfa,
+                theirs: CoinType::Gandalf,
+            })
+        );
+        ours.merge_cross_coin(&gandalf);
+    }
+
+    #[test]
+    fn test_holder_lookup_uses_latest_snapshot_at_or_before_block() {
+        let mut registry = CoinHolderRegistry::new(CoinType::Semantic);
+        registry.register_holder(b"holder".to_vec(), 100, 100);
+        registry.register_holder(b"holder".to_vec(), 700, 300);
+        // A shard signed at block 200 sees the block-100 snapshot.
+        let snapshot = registry.holder_at_block(b"holder", 200).expect("registered by 200");
+        assert_eq!(snapshot.block_height, 100);
+        assert_eq!(snapshot.balance, 100);
+        assert!(registry.verify_holder_at_block(b"holder", 200));
+        // Before the first snapshot the holder does not exist yet.
+        assert_eq!(registry.holder_at_block(b"holder", 99), None);
+        assert!(!registry.verify_holder_at_block(b"holder", 99));
+    }
+
+    #[test]
+    fn test_shard_document_counts() {
+        let mut system = ShardingSystem::new(DataType::Fano, CoinType::ERdfa).expect("valid type");
+        let sharded = system.shard_document(b"escaped rdfa", 100).expect("within limit");
+        assert_eq!(sharded.shards.len(), 7);
+        assert_eq!(sharded.total_shards, 7);
+    }
+
+    #[test]
+    fn test_holding_proof_roundtrip() {
+        let mut system = ShardingSystem::new(DataType::Triad, CoinType::ERdfa).expect("valid type");
+        let mut sharded = system.shard_document(b"escaped rdfa", 100).expect("within limit");
+        sharded.shards[1].holder_address = b"alice".to_vec();
+        let proof = sharded
+            .prove_holding(1, b"alice")
+            .expect("alice custodies shard 1");
+        assert!(sharded.verify_holding(&proof));
+        // Alice cannot prove holding a shard she doesn't custody.
+        assert!(sharded.prove_holding(2, b"alice").is_none());
+        // A proof reassigned to another holder fails verification.
+        let mut forged = proof.clone();
+        forged.holder_address = b"mallory".to_vec();
+        assert!(!sharded.verify_holding(&forged));
+    }
+
+    #[test]
+    fn test_reshare_refuses_policy_downgrade() {
+        let mut system = ShardingSystem::new(DataType::Gandalf, CoinType::Gandalf).expect("valid type");
+        assert_eq!(
+            system.reshare(50, 71),
+            Err(ShardError::ThresholdBelowPolicy {
+                requested: 50,
+                min: 71,
+            })
+        );
+        // The refused reshare must leave the old threshold in place.
+        assert_eq!(system.shamir.threshold, 71);
+        assert_eq!(system.reshare(71, 71), Ok(()));
+        assert_eq!(
+            system.reshare(72, 71),
+            Err(ShardError::InvalidThreshold {
+                requested: 72,
+                total: 71,
+            })
+        );
+    }
+
+    #[test]
+    fn test_custom_shard_counts_are_validated() {
+        assert_eq!(
+            ShardingSystem::new(DataType::Custom(1), CoinType::ERdfa).err(),
+            Some(ShardError::InvalidShardCount {
+                requested: 1,
+                max: MAX_CUSTOM_SHARDS,
+            })
+        );
+        let system = ShardingSystem::new(DataType::Custom(2), CoinType::ERdfa)
+            .expect("two shards is a real sharing");
+        assert_eq!(system.shamir.total_shares, 2);
+        assert_eq!(
+            ShardingSystem::new(DataType::Custom(500_000), CoinType::ERdfa).err(),
+            Some(ShardError::InvalidShardCount {
+                requested: 500_000,
+                max: MAX_CUSTOM_SHARDS,
+            })
+        );
+        // A caller-supplied cap overrides the default.
+        assert!(
+            ShardingSystem::new_with_custom_limit(DataType::Custom(10), CoinType::ERdfa, 8)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_monster_system_does_not_materialize_shards() {
+        // Construction and the eager-path refusal are both O(1); only
+        // the streaming iterator produces shards, one at a time.
+        let mut system = ShardingSystem::new(DataType::Monster, CoinType::Semantic).expect("valid type");
+        let err = system
+            .shard_document(b"escaped rdfa", 100)
+            .expect_err("monster must refuse the eager path");
+        assert_eq!(
+            err,
+            ShardError::TooManyShards {
+                requested: 196_883,
+                max: MAX_EAGER_SHARDS,
+            }
+        );
+        let mut stream = system.shard_document_streaming(b"escaped rdfa", 100);
+        assert_eq!(stream.len(), 196_883);
+        let first = stream.next().expect("monster yields shards");
+        assert_eq!(first.shard_id, 0);
+        assert_eq!(stream.next().map(|s| s.shard_id), Some(1));
+    }
+
+    #[test]
+    fn test_signed_shards_reconstruct() {
+        let mut system = ShardingSystem::new(DataType::Triad, CoinType::ERdfa).expect("valid type");
+        let mut sharded = system.shard_document(b"escaped rdfa", 100).expect("within limit");
+        for shard in &mut sharded.shards {
+            system.sign_shard(shard, b"holder key");
+        }
+        assert_eq!(
+            system.reconstruct_document(&sharded, b"holder key").as_deref(),
+            Some(b"escaped rdfa".as_slice())
+        );
+        // Tampered shard data no longer verifies, dropping the valid
+        // count below the 3-of-3 threshold.
+        sharded.shards[1].data[0] ^= 0xff;
+        assert!(!system.verify_signature(&sharded.shards[1], b"holder key"));
+        assert_eq!(system.reconstruct_document(&sharded, b"holder key"), None);
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_zeroizing_reconstruction_wipes_secret_material() {
+        let mut system = ShardingSystem::new(DataType::Triad, CoinType::ERdfa).expect("valid type");
+        let mut sharded = system.shard_document(b"escaped rdfa", 100).expect("within limit");
+        for shard in &mut sharded.shards {
+            system.sign_shard(shard, b"holder key");
+        }
+        let document = system
+            .reconstruct_document_zeroizing(&sharded, b"holder key")
+            .expect("full quorum");
+        assert_eq!(document.as_slice(), b"escaped rdfa");
+        // Explicit zeroization clears a scratch copy entirely.
+        let mut scratch = document.to_vec();
+        scratch.zeroize();
+        assert!(scratch.is_empty());
+    }
+
+    #[test]
+    fn test_instrumented_reconstruction_stats_are_consistent() {
+        let mut system = ShardingSystem::with_threshold(DataType::Octonion, CoinType::ERdfa, 5)
+            .expect("valid type");
+        let mut sharded = system.shard_document(b"escaped rdfa", 100).expect("within limit");
+        for shard in sharded.shards.iter_mut().take(6) {
+            system.sign_shard(shard, b"holder key");
+        }
+        let (document, stats) = system.reconstruct_document_instrumented(&sharded, b"holder key");
+        assert_eq!(document.as_deref(), Some(b"escaped rdfa".as_slice()));
+        assert_eq!(stats.verified_shards, 6);
+        assert_eq!(stats.failed_shards, 2);
+        assert_eq!(stats.verified_shards + stats.failed_shards, sharded.shards.len());
+        // The plain variant is the instrumented one minus the stats.
+        assert_eq!(
+            system.reconstruct_document(&sharded, b"holder key"),
+            document
+        );
+        // Below the quorum, interpolation never runs.
+        for shard in sharded.shards.iter_mut().skip(2) {
+            shard.signature.clear();
+        }
+        let (document, stats) = system.reconstruct_document_instrumented(&sharded, b"holder key");
+        assert_eq!(document, None);
+        assert_eq!(stats.verified_shards, 2);
+        assert_eq!(stats.interpolate_time_us, 0);
+    }
+
+    #[test]
+    fn test_with_threshold_reconstructs_from_quorum() {
+        let mut system = ShardingSystem::with_threshold(DataType::Octonion, CoinType::ERdfa, 5)
+            .expect("valid type");
+        let mut sharded = system.shard_document(b"escaped rdfa", 100).expect("within limit");
+        assert_eq!(sharded.total_shards, 8);
+        assert_eq!(sharded.required_shards, 5);
+        // Only five holders sign; the other three shards are dead.
+        for shard in sharded.shards.iter_mut().take(5) {
+            system.sign_shard(shard, b"holder key");
+        }
+        assert_eq!(
+            system.reconstruct_document(&sharded, b"holder key").as_deref(),
+            Some(b"escaped rdfa".as_slice())
+        );
+        // Four signed shards are one short of the quorum.
+        sharded.shards[4].signature.clear();
+        assert_eq!(system.reconstruct_document(&sharded, b"holder key"), None);
+    }
+
+    #[test]
+    fn test_reconstruction_checks_document_id() {
+        let mut system = ShardingSystem::new(DataType::Triad, CoinType::ERdfa).expect("valid type");
+        let mut sharded = system.shard_document(b"escaped rdfa", 100).expect("within limit");
+        for shard in &mut sharded.shards {
+            system.sign_shard(shard, b"holder key");
+        }
+        // Flip the same bit in two bytes: the XOR signature still
+        // verifies, but the interpolated document changes.
+        sharded.shards[0].data[0] ^= 0x01;
+        sharded.shards[0].data[1] ^= 0x01;
+        assert!(system.verify_signature(&sharded.shards[0], b"holder key"));
+        assert_eq!(system.reconstruct_document(&sharded, b"holder key"), None);
+    }
+
+    #[test]
+    fn test_hash_document_is_order_sensitive() {
+        assert_ne!(hash_document(b"ab"), hash_document(b"ba"));
+        assert_eq!(hash_document(b"escaped"), hash_document(b"escaped"));
+    }
+
+    #[test]
+    fn test_shard_document_with_progress() {
+        let mut system = ShardingSystem::new(DataType::Gandalf, CoinType::Gandalf).expect("valid type");
+        let mut calls = Vec::new();
+        system
+            .shard_document_with_progress(b"the seventy one", 100, &mut |done, total| {
+                calls.push((done, total));
+            })
+            .expect("within limit");
+        assert_eq!(calls.len(), 71);
+        assert_eq!(calls.first(), Some(&(1, 71)));
+        assert_eq!(calls.last(), Some(&(71, 71)));
+    }
+}
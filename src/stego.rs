@@ -0,0 +1,1545 @@
+//! Steganographic transports for eRDFa payloads.
+//!
+//! When even HTML-escaped markup is stripped by a platform, the payload
+//! can be hidden in channels the sanitizer is unlikely to touch: zero
+//! width characters, homoglyphs, whitespace runs, comments, attributes,
+//! or purely visual carriers (positions, colors, bitmaps).
+
+use crate::{escape, unescape};
+
+/// How the payload is hidden in the carrier document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StegoStrategy {
+    /// Plain HTML escaping, the baseline eRDFa transport.
+    HtmlEscape,
+    /// Zero-width characters interleaved into visible text.
+    ZeroWidth,
+    /// Bits carried in the width of whitespace runs.
+    Whitespace,
+    /// Latin letters swapped for Cyrillic homoglyphs.
+    Unicode,
+    /// Payload inside an HTML comment.
+    CommentEmbed,
+    /// Payload inside a `data-erdfa` attribute.
+    DataAttribute,
+    /// Payload inside a `display:none` element.
+    HiddenDiv,
+    /// Payload appended as a dedicated `"_erdfa"` JSON field.
+    JsonField,
+    /// Several strategies stacked on top of each other.
+    MultiLayer,
+    /// Visual carriers, see [`visual`].
+    Position,
+    Color,
+    FontSize,
+    CssProperty,
+    Bitmap,
+    VisualNoise,
+    QrCode,
+}
+
+impl StegoStrategy {
+    /// Every strategy, for exhaustive iteration in tests and tooling.
+    pub const ALL: [StegoStrategy; 16] = [
+        StegoStrategy::HtmlEscape,
+        StegoStrategy::ZeroWidth,
+        StegoStrategy::Whitespace,
+        StegoStrategy::Unicode,
+        StegoStrategy::CommentEmbed,
+        StegoStrategy::DataAttribute,
+        StegoStrategy::HiddenDiv,
+        StegoStrategy::JsonField,
+        StegoStrategy::MultiLayer,
+        StegoStrategy::Position,
+        StegoStrategy::Color,
+        StegoStrategy::FontSize,
+        StegoStrategy::CssProperty,
+        StegoStrategy::Bitmap,
+        StegoStrategy::VisualNoise,
+        StegoStrategy::QrCode,
+    ];
+
+    /// Stable wire name, used by payload tags.
+    pub fn name(&self) -> &'static str {
+        match self {
+            StegoStrategy::HtmlEscape => "html-escape",
+            StegoStrategy::ZeroWidth => "zero-width",
+            StegoStrategy::Whitespace => "whitespace",
+            StegoStrategy::Unicode => "unicode",
+            StegoStrategy::CommentEmbed => "comment",
+            StegoStrategy::DataAttribute => "data-attribute",
+            StegoStrategy::HiddenDiv => "hidden-div",
+            StegoStrategy::JsonField => "json-field",
+            StegoStrategy::MultiLayer => "multi-layer",
+            StegoStrategy::Position => "position",
+            StegoStrategy::Color => "color",
+            StegoStrategy::FontSize => "font-size",
+            StegoStrategy::CssProperty => "css-property",
+            StegoStrategy::Bitmap => "bitmap",
+            StegoStrategy::VisualNoise => "visual-noise",
+            StegoStrategy::QrCode => "qr-code",
+        }
+    }
+
+    /// Parse a wire name back into a strategy.
+    pub fn from_name(name: &str) -> Option<StegoStrategy> {
+        Self::ALL.iter().copied().find(|s| s.name() == name)
+    }
+}
+
+/// Errors from encoders that write into fixed-size carriers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StegoError {
+    /// The carrier cannot hold the payload.
+    CarrierTooSmall { needed: usize, available: usize },
+    /// A layer stack must contain at least one strategy.
+    EmptyLayerSpec,
+    /// A layer stack may not contain `MultiLayer` itself.
+    RecursiveLayerSpec,
+}
+
+/// How aggressively the target platform rewrites content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HostilityLevel {
+    /// Platform preserves escaped entities (e.g. Blogger).
+    Friendly,
+    /// Platform keeps simple markup but may rewrite entities.
+    Normal,
+    /// Platform strips unknown tags and attributes.
+    Hostile,
+    /// Platform re-encodes text but keeps invisible characters.
+    MaximumHostile,
+    /// Platform normalizes text entirely; only visuals survive.
+    Paranoid,
+    /// Hostility beyond the named tiers; orders above `Paranoid`.
+    Custom(u8),
+}
+
+/// Latin letters with indistinguishable Cyrillic twins, both cases.
+const HOMOGLYPHS: &[(char, char)] = &[
+    ('a', 'а'),
+    ('c', 'с'),
+    ('e', 'е'),
+    ('o', 'о'),
+    ('p', 'р'),
+    ('x', 'х'),
+    ('y', 'у'),
+    ('A', 'А'),
+    ('B', 'В'),
+    ('C', 'С'),
+    ('E', 'Е'),
+    ('H', 'Н'),
+    ('K', 'К'),
+    ('M', 'М'),
+    ('O', 'О'),
+    ('P', 'Р'),
+    ('T', 'Т'),
+    ('X', 'Х'),
+];
+
+/// Prefixed to characters that were already Cyrillic in the input, so
+/// `decode_unicode` does not "correct" them back to Latin.
+const HOMOGLYPH_LITERAL_MARKER: char = '\u{200D}';
+
+const ZERO_WIDTH_ZERO: char = '\u{200B}';
+const ZERO_WIDTH_ONE: char = '\u{200C}';
+
+/// The payload between `prefix` and `suffix`, located anywhere within
+/// `encoded` — hosting platforms routinely wrap our markup in their
+/// own, so decoders must not demand the marker be the entire input.
+fn slice_between<'a>(encoded: &'a str, prefix: &str, suffix: &str) -> Option<&'a str> {
+    let start = encoded.find(prefix)? + prefix.len();
+    let end = encoded[start..].find(suffix)? + start;
+    Some(&encoded[start..end])
+}
+
+/// CRC-32 (IEEE), bitwise; plenty fast for payload-sized inputs.
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffffu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// Common interface over all encoders.
+pub trait StegoEncoder {
+    /// Encode `data` under `strategy`.
+    ///
+    /// Must be a pure function of `self` and its inputs: encoding the
+    /// same data twice yields byte-identical output. Round-trip tests
+    /// and deduplicating transports rely on this, so a strategy that
+    /// wants randomness (position jitter, say) has to take its entropy
+    /// as explicit input rather than drawing it internally.
+    fn encode(&self, data: &str, strategy: StegoStrategy) -> String;
+    fn decode(&self, encoded: &str, strategy: StegoStrategy) -> Option<String>;
+
+    /// How many payload bytes a carrier of `carrier_len` units can hold
+    /// under `strategy`. Strategies that synthesize their own carrier
+    /// are unbounded and return `usize::MAX`. Callers should check this
+    /// before encoding into a fixed-size carrier.
+    fn capacity(&self, strategy: StegoStrategy, carrier_len: usize) -> usize;
+}
+
+/// The eRDFa reference encoder.
+pub struct ERdfaStego {
+    /// Layer stack applied by [`StegoStrategy::MultiLayer`]; `None`
+    /// keeps the historical escape-escape-comment stack.
+    multi_layer_spec: Option<Vec<StegoStrategy>>,
+    /// The `(zero, one)` characters used by `ZeroWidth`.
+    zero_width: (char, char),
+    /// The confusables table used by `Unicode`.
+    homoglyphs: Vec<(char, char)>,
+}
+
+impl Default for ERdfaStego {
+    fn default() -> Self {
+        ERdfaStego {
+            multi_layer_spec: None,
+            zero_width: (ZERO_WIDTH_ZERO, ZERO_WIDTH_ONE),
+            homoglyphs: HOMOGLYPHS.to_vec(),
+        }
+    }
+}
+
+/// Configures an [`ERdfaStego`] whose strategies need non-default
+/// alphabets or layer stacks. Obtained from [`ERdfaStego::builder`];
+/// every field defaults to the reference encoder's behaviour.
+pub struct ERdfaStegoBuilder {
+    stego: ERdfaStego,
+}
+
+impl ERdfaStegoBuilder {
+    /// The `(zero, one)` alphabet used by `ZeroWidth`. The two
+    /// characters must differ, or decoding is ambiguous.
+    pub fn zero_width_alphabet(mut self, zero: char, one: char) -> Self {
+        self.stego.zero_width = (zero, one);
+        self
+    }
+
+    /// The confusables table used by `Unicode`, as `(plain, twin)`
+    /// pairs.
+    pub fn homoglyphs(mut self, table: Vec<(char, char)>) -> Self {
+        self.stego.homoglyphs = table;
+        self
+    }
+
+    /// The layer stack applied by `MultiLayer`, in encode order.
+    pub fn multi_layer_spec(mut self, spec: Vec<StegoStrategy>) -> Self {
+        self.stego.multi_layer_spec = Some(spec);
+        self
+    }
+
+    pub fn build(self) -> ERdfaStego {
+        self.stego
+    }
+}
+
+impl ERdfaStego {
+    /// An encoder with default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start configuring a non-default encoder.
+    pub fn builder() -> ERdfaStegoBuilder {
+        ERdfaStegoBuilder {
+            stego: ERdfaStego::default(),
+        }
+    }
+
+    /// An encoder whose `MultiLayer` strategy applies `spec` in order
+    /// on encode and in reverse on decode.
+    pub fn with_multi_layer_spec(spec: Vec<StegoStrategy>) -> Self {
+        ERdfaStego {
+            multi_layer_spec: Some(spec),
+            ..Self::default()
+        }
+    }
+    /// The most hostile environment each strategy is expected to survive.
+    pub fn max_hostility(&self, strategy: StegoStrategy) -> HostilityLevel {
+        match strategy {
+            StegoStrategy::HtmlEscape => HostilityLevel::Friendly,
+            StegoStrategy::CommentEmbed => HostilityLevel::Friendly,
+            StegoStrategy::DataAttribute => HostilityLevel::Normal,
+            StegoStrategy::HiddenDiv => HostilityLevel::Normal,
+            // A real JSON field survives re-serialization.
+            StegoStrategy::JsonField => HostilityLevel::Hostile,
+            StegoStrategy::Whitespace => HostilityLevel::Hostile,
+            StegoStrategy::Unicode => HostilityLevel::Hostile,
+            StegoStrategy::ZeroWidth => HostilityLevel::MaximumHostile,
+            StegoStrategy::MultiLayer => HostilityLevel::MaximumHostile,
+            // Bitmap and noise carriers survive any text sanitization,
+            // as does the QR placeholder once rendered.
+            StegoStrategy::Bitmap => HostilityLevel::MaximumHostile,
+            StegoStrategy::VisualNoise => HostilityLevel::MaximumHostile,
+            StegoStrategy::QrCode => HostilityLevel::MaximumHostile,
+            // Layout and style channels survive even full text
+            // normalization.
+            StegoStrategy::Position => HostilityLevel::Paranoid,
+            StegoStrategy::Color => HostilityLevel::Paranoid,
+            StegoStrategy::FontSize => HostilityLevel::Paranoid,
+            StegoStrategy::CssProperty => HostilityLevel::Paranoid,
+        }
+    }
+
+    /// Pick the strongest strategy expected to survive the given
+    /// hostility level.
+    pub fn select_strategy(&self, level: HostilityLevel) -> StegoStrategy {
+        match level {
+            HostilityLevel::Friendly => StegoStrategy::HtmlEscape,
+            HostilityLevel::Normal => StegoStrategy::DataAttribute,
+            HostilityLevel::Hostile => StegoStrategy::Whitespace,
+            HostilityLevel::Paranoid => StegoStrategy::Color,
+            // MaximumHostile and any unnamed stronger tier fall back to
+            // layering everything; new variants select the most robust
+            // strategy instead of breaking this match.
+            _ => StegoStrategy::MultiLayer,
+        }
+    }
+
+    /// Guess which strategy produced `encoded` by inspecting its
+    /// markers, returning the most specific match. `None` means no
+    /// known marker is present; ambiguous payloads resolve to the
+    /// first (most specific) marker found.
+    pub fn detect_strategy(&self, encoded: &str) -> Option<StegoStrategy> {
+        if encoded.contains("data-erdfa=") {
+            return Some(StegoStrategy::DataAttribute);
+        }
+        if encoded.contains("\"_erdfa\":") {
+            return Some(StegoStrategy::JsonField);
+        }
+        if encoded.contains("--erdfa-data:") {
+            return Some(StegoStrategy::CssProperty);
+        }
+        if encoded.contains("display:none") {
+            return Some(StegoStrategy::HiddenDiv);
+        }
+        if encoded.contains("erdfa-qr:") {
+            return Some(StegoStrategy::QrCode);
+        }
+        if encoded.contains("<!--") {
+            return Some(StegoStrategy::CommentEmbed);
+        }
+        if encoded.contains(self.zero_width.0) || encoded.contains(self.zero_width.1) {
+            return Some(StegoStrategy::ZeroWidth);
+        }
+        if !encoded.is_empty() && encoded.chars().all(|c| matches!(c, ' ' | '\t' | '\n')) {
+            return Some(StegoStrategy::Whitespace);
+        }
+        if encoded
+            .chars()
+            .any(|c| self.homoglyphs.iter().any(|&(_, cyrillic)| cyrillic == c))
+        {
+            return Some(StegoStrategy::Unicode);
+        }
+        if encoded.contains("&lt;") || encoded.contains("&quot;") {
+            return Some(StegoStrategy::HtmlEscape);
+        }
+        None
+    }
+
+    /// Candidate strategies for a MIME type hint. `None` or an unknown
+    /// type falls back to every text strategy, most specific first so
+    /// the always-succeeding `HtmlEscape` cannot shadow a real carrier.
+    fn candidates_for_mime(mime: Option<&str>) -> &'static [StegoStrategy] {
+        match mime {
+            Some("image/svg+xml") => &[StegoStrategy::QrCode],
+            Some("application/json") => &[StegoStrategy::JsonField],
+            Some("text/css") => &[StegoStrategy::CssProperty],
+            Some("text/html") => &[
+                StegoStrategy::DataAttribute,
+                StegoStrategy::HiddenDiv,
+                StegoStrategy::CssProperty,
+                StegoStrategy::CommentEmbed,
+                StegoStrategy::HtmlEscape,
+            ],
+            _ => &[
+                StegoStrategy::DataAttribute,
+                StegoStrategy::JsonField,
+                StegoStrategy::CssProperty,
+                StegoStrategy::HiddenDiv,
+                StegoStrategy::QrCode,
+                StegoStrategy::CommentEmbed,
+                StegoStrategy::ZeroWidth,
+                StegoStrategy::Whitespace,
+                StegoStrategy::Unicode,
+                StegoStrategy::HtmlEscape,
+            ],
+        }
+    }
+
+    /// Decode with content-type sniffing: the MIME hint narrows the
+    /// candidate strategies, a detected marker jumps the queue, and the
+    /// first successful decode wins. Returns the payload together with
+    /// the strategy that produced it; `None` when no candidate decodes
+    /// the blob.
+    pub fn decode_auto(&self, encoded: &str, mime: Option<&str>) -> Option<(StegoStrategy, String)> {
+        let candidates = Self::candidates_for_mime(mime);
+        if let Some(detected) = self.detect_strategy(encoded) {
+            if candidates.contains(&detected) {
+                if let Some(decoded) = self.decode(encoded, detected) {
+                    return Some((detected, decoded));
+                }
+            }
+        }
+        candidates.iter().find_map(|&strategy| {
+            self.decode(encoded, strategy)
+                .map(|decoded| (strategy, decoded))
+        })
+    }
+
+    fn encode_zero_width(&self, data: &str) -> String {
+        let mut out = String::new();
+        for byte in data.bytes() {
+            for bit in (0..8).rev() {
+                out.push(if (byte >> bit) & 1 == 1 {
+                    self.zero_width.1
+                } else {
+                    self.zero_width.0
+                });
+            }
+        }
+        out
+    }
+
+    fn decode_zero_width(&self, encoded: &str) -> Option<String> {
+        let bits: Vec<u8> = encoded
+            .chars()
+            .filter_map(|c| {
+                if c == self.zero_width.0 {
+                    Some(0)
+                } else if c == self.zero_width.1 {
+                    Some(1)
+                } else {
+                    None
+                }
+            })
+            .collect();
+        if bits.is_empty() || !bits.len().is_multiple_of(8) {
+            return None;
+        }
+        let bytes: Vec<u8> = bits
+            .chunks(8)
+            .map(|chunk| chunk.iter().fold(0u8, |acc, &b| (acc << 1) | b))
+            .collect();
+        String::from_utf8(bytes).ok()
+    }
+
+    /// Each byte becomes a group of eight whitespace tokens, one token
+    /// per bit (most significant first): one space is a 0, two spaces
+    /// are a 1. Tokens within a group are separated by tabs, groups by
+    /// newlines, so `decode_whitespace` can reassemble exact bytes.
+    fn encode_whitespace(&self, data: &str) -> String {
+        let mut out = String::new();
+        for (group, byte) in data.bytes().enumerate() {
+            if group > 0 {
+                out.push('\n');
+            }
+            for bit in (0..8).rev() {
+                if bit < 7 {
+                    out.push('\t');
+                }
+                out.push(' ');
+                if (byte >> bit) & 1 == 1 {
+                    out.push(' ');
+                }
+            }
+        }
+        out
+    }
+
+    fn decode_whitespace(&self, encoded: &str) -> Option<String> {
+        if encoded.is_empty() {
+            return Some(String::new());
+        }
+        let mut bytes = Vec::new();
+        for group in encoded.split('\n') {
+            let tokens: Vec<&str> = group.split('\t').collect();
+            if tokens.len() != 8 {
+                return None;
+            }
+            let mut byte = 0u8;
+            for token in tokens {
+                let bit = match token.len() {
+                    1 => 0,
+                    2 => 1,
+                    _ => return None,
+                };
+                byte = (byte << 1) | bit;
+            }
+            bytes.push(byte);
+        }
+        String::from_utf8(bytes).ok()
+    }
+
+    /// NFC-normalize text entering the homoglyph transform, so the same
+    /// logical string encodes identically regardless of which
+    /// normalization form it arrived in. Without the
+    /// `unicode-normalization` feature this is the identity.
+    #[cfg(feature = "unicode-normalization")]
+    fn nfc(data: &str) -> String {
+        use unicode_normalization::UnicodeNormalization;
+        data.nfc().collect()
+    }
+
+    #[cfg(not(feature = "unicode-normalization"))]
+    fn nfc(data: &str) -> String {
+        data.to_string()
+    }
+
+    /// Swap Latin letters for their Cyrillic twins. For ASCII-only input
+    /// this is fully reversible: `decode_unicode(encode_unicode(s)) == s`.
+    /// Input that already contains one of the Cyrillic twins gets it
+    /// prefixed with [`HOMOGLYPH_LITERAL_MARKER`] so decoding leaves the
+    /// genuine Cyrillic character untouched. Input is NFC-normalized
+    /// first when the `unicode-normalization` feature is enabled.
+    fn encode_unicode(&self, data: &str) -> String {
+        let data = Self::nfc(data);
+        let mut out = String::new();
+        for c in data.chars() {
+            if self.homoglyphs.iter().any(|&(_, cyrillic)| cyrillic == c) {
+                out.push(HOMOGLYPH_LITERAL_MARKER);
+                out.push(c);
+            } else if let Some(&(_, cyrillic)) =
+                self.homoglyphs.iter().find(|&&(latin, _)| latin == c)
+            {
+                out.push(cyrillic);
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    }
+
+    fn decode_unicode(&self, encoded: &str) -> Option<String> {
+        let encoded = Self::nfc(encoded);
+        let mut out = String::new();
+        let mut literal = false;
+        for c in encoded.chars() {
+            if c == HOMOGLYPH_LITERAL_MARKER {
+                literal = true;
+                continue;
+            }
+            if !literal {
+                if let Some(&(latin, _)) = self.homoglyphs.iter().find(|&&(_, cyrillic)| cyrillic == c)
+                {
+                    out.push(latin);
+                    continue;
+                }
+            }
+            out.push(c);
+            literal = false;
+        }
+        Some(out)
+    }
+
+    fn encode_comment(&self, data: &str) -> String {
+        format!("<!--erdfa:{}-->", escape(data))
+    }
+
+    fn decode_comment(&self, encoded: &str) -> Option<String> {
+        let body = slice_between(encoded, "<!--erdfa:", "-->")?;
+        Some(unescape(body))
+    }
+
+    fn encode_data_attribute(&self, data: &str) -> String {
+        format!("<span data-erdfa=\"{}\"></span>", escape(data))
+    }
+
+    fn decode_data_attribute(&self, encoded: &str) -> Option<String> {
+        let body = slice_between(encoded, "<span data-erdfa=\"", "\"></span>")?;
+        Some(unescape(body))
+    }
+
+    fn encode_hidden_div(&self, data: &str) -> String {
+        format!("<div style=\"display:none\">{}</div>", escape(data))
+    }
+
+    fn decode_hidden_div(&self, encoded: &str) -> Option<String> {
+        let body = slice_between(encoded, "<div style=\"display:none\">", "</div>")?;
+        Some(unescape(body))
+    }
+
+    /// Apply each strategy in order, innermost first. Empty stacks and
+    /// stacks containing `MultiLayer` itself (which would recurse) are
+    /// rejected.
+    pub fn encode_layers(&self, data: &str, layers: &[StegoStrategy]) -> Result<String, StegoError> {
+        if layers.is_empty() {
+            return Err(StegoError::EmptyLayerSpec);
+        }
+        if layers.contains(&StegoStrategy::MultiLayer) {
+            return Err(StegoError::RecursiveLayerSpec);
+        }
+        Ok(layers
+            .iter()
+            .fold(data.to_string(), |acc, &layer| self.encode(&acc, layer)))
+    }
+
+    /// Reverse [`encode_layers`](Self::encode_layers), peeling the
+    /// outermost strategy first.
+    pub fn decode_layers(&self, encoded: &str, layers: &[StegoStrategy]) -> Option<String> {
+        if layers.is_empty() || layers.contains(&StegoStrategy::MultiLayer) {
+            return None;
+        }
+        layers
+            .iter()
+            .rev()
+            .try_fold(encoded.to_string(), |acc, &layer| self.decode(&acc, layer))
+    }
+
+    fn encode_multi_layer(&self, data: &str) -> String {
+        match &self.multi_layer_spec {
+            Some(spec) => self
+                .encode_layers(data, spec)
+                // An unusable spec falls back to the default stack.
+                .unwrap_or_else(|_| self.encode_comment(&escape(&escape(data)))),
+            // Default: escape twice, then hide the result in a comment.
+            None => self.encode_comment(&escape(&escape(data))),
+        }
+    }
+
+    fn decode_multi_layer(&self, encoded: &str) -> Option<String> {
+        match &self.multi_layer_spec {
+            Some(spec) if !spec.is_empty() && !spec.contains(&StegoStrategy::MultiLayer) => {
+                self.decode_layers(encoded, spec)
+            }
+            _ => {
+                let inner = self.decode_comment(encoded)?;
+                Some(unescape(&unescape(&inner)))
+            }
+        }
+    }
+
+    /// Repeating-key XOR stream; applying it twice is identity. A real
+    /// cipher can replace this behind a future `cipher` feature without
+    /// changing the embedding format.
+    fn xor_stream(data: &[u8], key: &[u8]) -> Vec<u8> {
+        if key.is_empty() {
+            return data.to_vec();
+        }
+        data.iter()
+            .enumerate()
+            .map(|(i, &byte)| byte ^ key[i % key.len()])
+            .collect()
+    }
+
+    /// Encrypt the payload with `key` before embedding, so strategies
+    /// like `DataAttribute` and `HiddenDiv` no longer expose plaintext
+    /// to anyone viewing source. The ciphertext is hex-armored and
+    /// carries a short magic so decoding with a wrong key fails.
+    pub fn encode_encrypted(&self, data: &str, strategy: StegoStrategy, key: &[u8]) -> String {
+        let mut plain = b"eR".to_vec();
+        plain.extend_from_slice(data.as_bytes());
+        let cipher = Self::xor_stream(&plain, key);
+        self.encode(&Self::to_hex(&cipher), strategy)
+    }
+
+    /// Reverse [`encode_encrypted`](Self::encode_encrypted); returns
+    /// `None` when the key is wrong.
+    pub fn decode_encrypted(
+        &self,
+        encoded: &str,
+        strategy: StegoStrategy,
+        key: &[u8],
+    ) -> Option<String> {
+        let hex = self.decode(encoded, strategy)?;
+        let plain = Self::xor_stream(&Self::from_hex(&hex)?, key);
+        let payload = plain.strip_prefix(b"eR")?;
+        String::from_utf8(payload.to_vec()).ok()
+    }
+
+    fn to_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn from_hex(hex: &str) -> Option<Vec<u8>> {
+        if !hex.len().is_multiple_of(2) {
+            return None;
+        }
+        (0..hex.len() / 2)
+            .map(|i| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok())
+            .collect()
+    }
+
+    /// Frame: 4-byte big-endian payload length, the payload, then a
+    /// CRC-32 of the payload, so truncation and corruption are caught
+    /// on unframe.
+    fn frame(data: &[u8]) -> Vec<u8> {
+        let mut framed = (data.len() as u32).to_be_bytes().to_vec();
+        framed.extend_from_slice(data);
+        framed.extend_from_slice(&crc32(data).to_be_bytes());
+        framed
+    }
+
+    fn unframe(framed: &[u8]) -> Option<Vec<u8>> {
+        if framed.len() < 8 {
+            return None;
+        }
+        let len = u32::from_be_bytes(framed[..4].try_into().ok()?) as usize;
+        if framed.len() != len + 8 {
+            return None;
+        }
+        let payload = &framed[4..4 + len];
+        let expected = u32::from_be_bytes(framed[4 + len..].try_into().ok()?);
+        if crc32(payload) != expected {
+            return None;
+        }
+        Some(payload.to_vec())
+    }
+
+    /// Encode with a self-describing tag recording the strategy and
+    /// whether the payload was framed (length + CRC), so
+    /// [`decode_tagged`](Self::decode_tagged) needs no out-of-band
+    /// knowledge of the encoding.
+    pub fn encode_tagged(&self, data: &str, strategy: StegoStrategy, framed: bool) -> String {
+        let payload = if framed {
+            Self::to_hex(&Self::frame(data.as_bytes()))
+        } else {
+            data.to_string()
+        };
+        format!(
+            "eRtag:{}:{}:{}",
+            strategy.name(),
+            u8::from(framed),
+            self.encode(&payload, strategy)
+        )
+    }
+
+    /// Decode a tagged payload, unframing automatically when the tag
+    /// says the payload was framed.
+    pub fn decode_tagged(&self, tagged: &str) -> Option<String> {
+        let rest = tagged.strip_prefix("eRtag:")?;
+        let (name, rest) = rest.split_once(':')?;
+        let (flag, encoded) = rest.split_once(':')?;
+        let decoded = self.decode(encoded, StegoStrategy::from_name(name)?)?;
+        match flag {
+            "0" => Some(decoded),
+            "1" => String::from_utf8(Self::unframe(&Self::from_hex(&decoded)?)?).ok(),
+            _ => None,
+        }
+    }
+
+    fn json_escape(data: &str) -> String {
+        data.replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('\n', "\\n")
+    }
+
+    /// Inverse of [`json_escape`](Self::json_escape). A single scan
+    /// that consumes each `\` with its follower, so a literal backslash
+    /// in the payload (escaped to `\\`) is never misread as the start
+    /// of a `\n` or `\"` escape by a later pass.
+    fn json_unescape(data: &str) -> String {
+        let mut out = String::with_capacity(data.len());
+        let mut chars = data.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                out.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('"') => out.push('"'),
+                Some('\\') => out.push('\\'),
+                // Not produced by json_escape; keep verbatim.
+                Some(other) => {
+                    out.push('\\');
+                    out.push(other);
+                }
+                None => out.push('\\'),
+            }
+        }
+        out
+    }
+
+    /// Append the payload as a `"_erdfa"` field to an existing JSON
+    /// object, surviving any re-serialization that keeps fields.
+    pub fn embed_json_field(&self, json: &str, data: &str) -> String {
+        let escaped = Self::json_escape(data);
+        match json.rfind('}') {
+            Some(brace) => {
+                let body_empty = json[..brace]
+                    .rfind('{')
+                    .map(|open| json[open + 1..brace].trim().is_empty())
+                    .unwrap_or(false);
+                let separator = if body_empty { "" } else { "," };
+                format!(
+                    "{}{}\"_erdfa\":\"{}\"{}",
+                    &json[..brace],
+                    separator,
+                    escaped,
+                    &json[brace..]
+                )
+            }
+            None => format!("{{\"_erdfa\":\"{}\"}}", escaped),
+        }
+    }
+
+    /// Read the `"_erdfa"` field back out of a JSON document.
+    pub fn extract_json_field(&self, json: &str) -> Option<String> {
+        let start = json.find("\"_erdfa\":\"")? + "\"_erdfa\":\"".len();
+        let mut end = None;
+        let mut escaped = false;
+        for (i, c) in json[start..].char_indices() {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                end = Some(start + i);
+                break;
+            }
+        }
+        Some(Self::json_unescape(&json[start..end?]))
+    }
+
+    fn encode_css_property(&self, data: &str) -> String {
+        format!("<div style=\"--erdfa-data:'{}'\"></div>", escape(data))
+    }
+
+    fn decode_css_property(&self, encoded: &str) -> Option<String> {
+        let body = slice_between(encoded, "<div style=\"--erdfa-data:'", "'\"></div>")?;
+        Some(unescape(body))
+    }
+}
+
+impl StegoEncoder for ERdfaStego {
+    fn encode(&self, data: &str, strategy: StegoStrategy) -> String {
+        match strategy {
+            StegoStrategy::HtmlEscape => escape(data),
+            StegoStrategy::ZeroWidth => self.encode_zero_width(data),
+            StegoStrategy::Whitespace => self.encode_whitespace(data),
+            StegoStrategy::Unicode => self.encode_unicode(data),
+            StegoStrategy::CommentEmbed => self.encode_comment(data),
+            StegoStrategy::DataAttribute => self.encode_data_attribute(data),
+            StegoStrategy::HiddenDiv => self.encode_hidden_div(data),
+            StegoStrategy::JsonField => self.embed_json_field("{}", data),
+            StegoStrategy::MultiLayer => self.encode_multi_layer(data),
+            StegoStrategy::Position => visual::format_positions(&visual::encode_position(data.as_bytes())),
+            StegoStrategy::Color => visual::encode_color(data.as_bytes()).join(";"),
+            StegoStrategy::FontSize => visual::encode_font_size(data.as_bytes())
+                .iter()
+                .map(|px| px.to_string())
+                .collect::<Vec<_>>()
+                .join(";"),
+            StegoStrategy::CssProperty => self.encode_css_property(data),
+            StegoStrategy::Bitmap => {
+                let mut carrier = vec![0u8; data.len() * 8];
+                visual::encode_bitmap_lsb(&mut carrier, data.as_bytes())
+                    .expect("carrier sized to payload");
+                carrier.iter().map(|b| format!("{:02x}", b)).collect()
+            }
+            StegoStrategy::VisualNoise => visual::encode_visual_noise(data.as_bytes())
+                .iter()
+                .map(|o| format!("{:.4}", o))
+                .collect::<Vec<_>>()
+                .join(";"),
+            StegoStrategy::QrCode => visual::encode_qr_code(data),
+        }
+    }
+
+    fn decode(&self, encoded: &str, strategy: StegoStrategy) -> Option<String> {
+        match strategy {
+            StegoStrategy::HtmlEscape => Some(unescape(encoded)),
+            StegoStrategy::ZeroWidth => self.decode_zero_width(encoded),
+            StegoStrategy::Whitespace => self.decode_whitespace(encoded),
+            StegoStrategy::Unicode => self.decode_unicode(encoded),
+            StegoStrategy::CommentEmbed => self.decode_comment(encoded),
+            StegoStrategy::DataAttribute => self.decode_data_attribute(encoded),
+            StegoStrategy::HiddenDiv => self.decode_hidden_div(encoded),
+            StegoStrategy::JsonField => self.extract_json_field(encoded),
+            StegoStrategy::MultiLayer => self.decode_multi_layer(encoded),
+            StegoStrategy::Position => {
+                let positions = visual::parse_positions(encoded)?;
+                String::from_utf8(visual::decode_position(&positions)).ok()
+            }
+            StegoStrategy::Color => {
+                let colors: Vec<String> = encoded.split(';').map(str::to_string).collect();
+                String::from_utf8(visual::decode_color(&colors)?).ok()
+            }
+            StegoStrategy::FontSize => {
+                let sizes: Result<Vec<u8>, _> =
+                    encoded.split(';').map(|s| s.parse::<u8>()).collect();
+                String::from_utf8(visual::decode_font_size(&sizes.ok()?)).ok()
+            }
+            StegoStrategy::CssProperty => self.decode_css_property(encoded),
+            StegoStrategy::Bitmap => {
+                if !encoded.len().is_multiple_of(16) {
+                    return None;
+                }
+                let carrier: Option<Vec<u8>> = (0..encoded.len() / 2)
+                    .map(|i| u8::from_str_radix(&encoded[i * 2..i * 2 + 2], 16).ok())
+                    .collect();
+                let carrier = carrier?;
+                let bytes = visual::decode_bitmap_lsb(&carrier, carrier.len() / 8)?;
+                String::from_utf8(bytes).ok()
+            }
+            StegoStrategy::VisualNoise => {
+                let opacities: Result<Vec<f32>, _> =
+                    encoded.split(';').map(|s| s.parse::<f32>()).collect();
+                String::from_utf8(visual::decode_visual_noise(&opacities.ok()?)).ok()
+            }
+            StegoStrategy::QrCode => visual::decode_qr_code(encoded),
+        }
+    }
+
+    fn capacity(&self, strategy: StegoStrategy, carrier_len: usize) -> usize {
+        match strategy {
+            // These synthesize their own carrier, so any payload fits.
+            StegoStrategy::HtmlEscape
+            | StegoStrategy::ZeroWidth
+            | StegoStrategy::Whitespace
+            | StegoStrategy::CommentEmbed
+            | StegoStrategy::DataAttribute
+            | StegoStrategy::HiddenDiv
+            | StegoStrategy::JsonField
+            | StegoStrategy::MultiLayer
+            | StegoStrategy::CssProperty
+            | StegoStrategy::QrCode => usize::MAX,
+            // The homoglyph channel carries at most the carrier text itself.
+            StegoStrategy::Unicode => carrier_len,
+            // One span per payload byte.
+            StegoStrategy::Position | StegoStrategy::FontSize | StegoStrategy::VisualNoise => {
+                carrier_len
+            }
+            // Three payload bytes per rgb() color span.
+            StegoStrategy::Color => carrier_len * 3,
+            // One payload bit per carrier byte.
+            StegoStrategy::Bitmap => carrier_len / 8,
+        }
+    }
+}
+
+/// Carriers that survive text normalization because the payload lives in
+/// layout and style rather than in characters.
+pub mod visual {
+    /// Spread each byte over a base-100 (x, y) pixel position: `x` is
+    /// the low digit pair (`0..100`), `y` the high (`0..3`), so every
+    /// byte value 0..=255 round-trips exactly.
+    pub fn encode_position(data: &[u8]) -> Vec<(i32, i32)> {
+        data.iter()
+            .map(|&b| (b as i32 % 100, b as i32 / 100))
+            .collect()
+    }
+
+    /// Reverse [`encode_position`]. Coordinates are reduced into their
+    /// base-100 ranges first so the reassembled value always fits a
+    /// byte instead of wrapping through an unchecked cast.
+    pub fn decode_position(positions: &[(i32, i32)]) -> Vec<u8> {
+        positions
+            .iter()
+            .map(|&(x, y)| {
+                let value = x.rem_euclid(100) + y.rem_euclid(100) * 100;
+                value.min(u8::MAX as i32) as u8
+            })
+            .collect()
+    }
+
+    pub(crate) fn format_positions(positions: &[(i32, i32)]) -> String {
+        positions
+            .iter()
+            .map(|&(x, y)| format!("{},{}", x, y))
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+
+    pub(crate) fn parse_positions(encoded: &str) -> Option<Vec<(i32, i32)>> {
+        encoded
+            .split(';')
+            .map(|pair| {
+                let (x, y) = pair.split_once(',')?;
+                Some((x.parse().ok()?, y.parse().ok()?))
+            })
+            .collect()
+    }
+
+    /// Pack bytes three at a time into CSS `rgb(...)` colors; the final
+    /// chunk is zero padded.
+    pub fn encode_color(data: &[u8]) -> Vec<String> {
+        data.chunks(3)
+            .map(|chunk| {
+                let mut rgb = [0u8; 3];
+                rgb[..chunk.len()].copy_from_slice(chunk);
+                format!("rgb({},{},{})", rgb[0], rgb[1], rgb[2])
+            })
+            .collect()
+    }
+
+    pub fn decode_color(colors: &[String]) -> Option<Vec<u8>> {
+        let mut bytes = Vec::new();
+        for color in colors {
+            let body = color.strip_prefix("rgb(")?.strip_suffix(")")?;
+            for part in body.split(',') {
+                bytes.push(part.trim().parse().ok()?);
+            }
+        }
+        Some(bytes)
+    }
+
+    /// Carry each byte directly as a font size in pixels.
+    pub fn encode_font_size(data: &[u8]) -> Vec<u8> {
+        data.to_vec()
+    }
+
+    pub fn decode_font_size(sizes: &[u8]) -> Vec<u8> {
+        sizes.to_vec()
+    }
+
+    /// Hide each byte in the fourth decimal of an opacity value.
+    pub fn encode_visual_noise(data: &[u8]) -> Vec<f32> {
+        data.iter().map(|&b| 0.9 + b as f32 / 10000.0).collect()
+    }
+
+    pub fn decode_visual_noise(opacities: &[f32]) -> Vec<u8> {
+        opacities
+            .iter()
+            .map(|&o| ((o - 0.9) * 10000.0).round() as u8)
+            .collect()
+    }
+
+    /// Write the payload into the least significant bit of each carrier
+    /// byte, most significant payload bit first. Fails without touching
+    /// the carrier if it cannot hold every payload bit.
+    pub fn encode_bitmap_lsb(carrier: &mut [u8], data: &[u8]) -> Result<(), super::StegoError> {
+        let needed = data.len() * 8;
+        if carrier.len() < needed {
+            return Err(super::StegoError::CarrierTooSmall {
+                needed,
+                available: carrier.len(),
+            });
+        }
+        for (i, &byte) in data.iter().enumerate() {
+            for bit in 0..8 {
+                let idx = i * 8 + bit;
+                carrier[idx] = (carrier[idx] & !1) | ((byte >> (7 - bit)) & 1);
+            }
+        }
+        Ok(())
+    }
+
+    /// Read `length` payload bytes back out of the carrier, or `None`
+    /// if the carrier is too short to contain them.
+    pub fn decode_bitmap_lsb(carrier: &[u8], length: usize) -> Option<Vec<u8>> {
+        if length * 8 > carrier.len() {
+            return None;
+        }
+        Some(
+            (0..length)
+                .map(|i| (0..8).fold(0u8, |acc, bit| (acc << 1) | (carrier[i * 8 + bit] & 1)))
+                .collect(),
+        )
+    }
+
+    /// Encode bytes as the coordinate deltas of an SVG path: each byte
+    /// becomes one relative `l dx dy` segment carrying its high and low
+    /// nibbles. Coordinates stay integral, so decoding is exact with no
+    /// float precision to worry about, and the result is a renderable
+    /// polyline — it survives sanitizers that keep SVG geometry while
+    /// stripping comments and metadata.
+    pub fn encode_svg_path(data: &[u8]) -> String {
+        let mut d = String::from("M 0 0");
+        for &byte in data {
+            d.push_str(&format!(" l {} {}", byte >> 4, byte & 0x0f));
+        }
+        format!("<svg xmlns=\"http://www.w3.org/2000/svg\"><path d=\"{}\"/></svg>", d)
+    }
+
+    /// Reverse [`encode_svg_path`]. `None` when the markup holds no
+    /// path, the path does not start at the origin, or a delta falls
+    /// outside the nibble range.
+    pub fn decode_svg_path(svg: &str) -> Option<Vec<u8>> {
+        let d = super::slice_between(svg, "d=\"", "\"")?;
+        let mut tokens = d.split_whitespace();
+        if (tokens.next()?, tokens.next()?, tokens.next()?) != ("M", "0", "0") {
+            return None;
+        }
+        let mut bytes = Vec::new();
+        while let Some(command) = tokens.next() {
+            if command != "l" {
+                return None;
+            }
+            let dx: u8 = tokens.next()?.parse().ok()?;
+            let dy: u8 = tokens.next()?.parse().ok()?;
+            if dx > 0x0f || dy > 0x0f {
+                return None;
+            }
+            bytes.push((dx << 4) | dy);
+        }
+        Some(bytes)
+    }
+
+    /// Placeholder QR carrier: the payload is stashed in an SVG comment
+    /// until real module-matrix generation lands behind the `qr`
+    /// feature.
+    pub fn encode_qr_code(data: &str) -> String {
+        format!(
+            "data:image/svg+xml,<svg xmlns=\"http://www.w3.org/2000/svg\"><!--erdfa-qr:{}--></svg>",
+            data
+        )
+    }
+
+    /// Reverse the comment wrapping of [`encode_qr_code`].
+    pub fn decode_qr_code(data_url: &str) -> Option<String> {
+        let start = data_url.find("<!--erdfa-qr:")? + "<!--erdfa-qr:".len();
+        let end = data_url[start..].find("-->")?;
+        Some(data_url[start..start + end].to_string())
+    }
+
+    /// Real QR module-matrix generation with selectable version and
+    /// error-correction level, behind the `qr` feature.
+    #[cfg(feature = "qr")]
+    pub mod qr {
+        /// QR error-correction levels.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum ErrorCorrection {
+            Low,
+            Medium,
+            Quartile,
+            High,
+        }
+
+        /// Render the payload as an SVG QR module matrix.
+        pub fn encode_qr_matrix(data: &str, version: u8, ec: ErrorCorrection) -> String {
+            // Placeholder for the real generator; the comment form
+            // remains the canonical interchange format.
+            let _ = (version, ec);
+            super::encode_qr_code(data)
+        }
+    }
+
+    /// Render the payload three ways at once (position, color, font
+    /// size) as absolutely positioned spans.
+    pub fn generate_visual_stego(data: &str) -> String {
+        let bytes = data.as_bytes();
+        let positions = encode_position(bytes);
+        let colors = encode_color(bytes);
+        let sizes = encode_font_size(bytes);
+        let mut html = String::from("<div class=\"erdfa-visual\" style=\"position:relative\">\n");
+        for (i, &(x, y)) in positions.iter().enumerate() {
+            html.push_str(&format!(
+                "<span style=\"position:absolute;left:{}px;top:{}px;font-size:{}px\">.</span>\n",
+                x, y, sizes[i]
+            ));
+        }
+        for color in &colors {
+            html.push_str(&format!("<span style=\"color:{}\">.</span>\n", color));
+        }
+        html.push_str("</div>");
+        html
+    }
+
+    /// Read a [`generate_visual_stego`] document back. The color spans
+    /// are the cleanest channel, so the payload is reconstructed via
+    /// [`decode_color`]; the absolutely positioned spans (one per
+    /// payload byte) give the true length, which trims the zero
+    /// padding of the final color chunk.
+    pub fn parse_visual_stego(html: &str) -> Option<String> {
+        let mut colors = Vec::new();
+        let mut payload_len = 0usize;
+        for segment in html.split("<span style=\"") {
+            if segment.starts_with("position:absolute;") {
+                payload_len += 1;
+            } else if let Some(rest) = segment.strip_prefix("color:") {
+                let end = rest.find('"')?;
+                colors.push(rest[..end].to_string());
+            }
+        }
+        let mut bytes = decode_color(&colors)?;
+        bytes.truncate(payload_len);
+        String::from_utf8(bytes).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_width_roundtrip() {
+        let stego = ERdfaStego::new();
+        let encoded = stego.encode("eRDFa", StegoStrategy::ZeroWidth);
+        assert_eq!(stego.decode(&encoded, StegoStrategy::ZeroWidth).as_deref(), Some("eRDFa"));
+    }
+
+    #[test]
+    fn test_encode_is_deterministic_for_every_strategy() {
+        // `StegoEncoder::encode` guarantees purity; catch any strategy
+        // that starts drawing entropy internally before it can make
+        // round-trips flaky.
+        let stego = ERdfaStego::new();
+        let payload = "deterministic eRDFa <payload> &amp; more";
+        for strategy in StegoStrategy::ALL {
+            assert_eq!(
+                stego.encode(payload, strategy),
+                stego.encode(payload, strategy),
+                "{} is not deterministic",
+                strategy.name()
+            );
+        }
+    }
+
+    #[test]
+    fn test_builder_custom_zero_width_alphabet() {
+        let stego = ERdfaStego::builder()
+            .zero_width_alphabet('\u{2060}', '\u{FEFF}')
+            .build();
+        let encoded = stego.encode("eRDFa", StegoStrategy::ZeroWidth);
+        assert!(!encoded.contains(ZERO_WIDTH_ZERO));
+        assert!(!encoded.contains(ZERO_WIDTH_ONE));
+        assert!(encoded.chars().all(|c| c == '\u{2060}' || c == '\u{FEFF}'));
+        assert_eq!(stego.decode(&encoded, StegoStrategy::ZeroWidth).as_deref(), Some("eRDFa"));
+        // The default encoder cannot read the custom alphabet.
+        assert_eq!(ERdfaStego::new().decode(&encoded, StegoStrategy::ZeroWidth), None);
+    }
+
+    #[test]
+    fn test_whitespace_roundtrip() {
+        let stego = ERdfaStego::new();
+        let encoded = stego.encode("ABC", StegoStrategy::Whitespace);
+        assert_eq!(stego.decode(&encoded, StegoStrategy::Whitespace).as_deref(), Some("ABC"));
+    }
+
+    #[test]
+    fn test_whitespace_groups_eight_tokens_per_byte() {
+        let stego = ERdfaStego::new();
+        let encoded = stego.encode("A", StegoStrategy::Whitespace);
+        assert_eq!(encoded.split('\t').count(), 8);
+        // 'A' = 0b01000001, one space per 0 bit, two per 1 bit.
+        assert_eq!(encoded, " \t  \t \t \t \t \t \t  ");
+    }
+
+    #[test]
+    fn test_encrypted_embedding_roundtrip() {
+        let stego = ERdfaStego::new();
+        let secret = "<p property=\"name\">Jim Dupont</p>";
+        let encoded = stego.encode_encrypted(secret, StegoStrategy::DataAttribute, b"passphrase");
+        assert!(!encoded.contains("Jim Dupont"));
+        assert_eq!(
+            stego
+                .decode_encrypted(&encoded, StegoStrategy::DataAttribute, b"passphrase")
+                .as_deref(),
+            Some(secret)
+        );
+        assert_eq!(
+            stego.decode_encrypted(&encoded, StegoStrategy::DataAttribute, b"wrong key"),
+            None
+        );
+    }
+
+    #[cfg(feature = "unicode-normalization")]
+    #[test]
+    fn test_unicode_encoding_is_normalization_stable() {
+        let stego = ERdfaStego::new();
+        // The same logical "résumé" in NFC and NFD forms.
+        let composed = "r\u{e9}sum\u{e9}";
+        let decomposed = "re\u{301}sume\u{301}";
+        assert_eq!(
+            stego.encode(composed, StegoStrategy::Unicode),
+            stego.encode(decomposed, StegoStrategy::Unicode)
+        );
+    }
+
+    #[test]
+    fn test_tagged_framed_roundtrip() {
+        let stego = ERdfaStego::new();
+        let secret = "<p property=\"name\">Jim Dupont</p>";
+        let tagged = stego.encode_tagged(secret, StegoStrategy::ZeroWidth, true);
+        // The caller needs nothing but the tagged blob.
+        assert_eq!(stego.decode_tagged(&tagged).as_deref(), Some(secret));
+        let untagged = stego.encode_tagged(secret, StegoStrategy::CommentEmbed, false);
+        assert_eq!(stego.decode_tagged(&untagged).as_deref(), Some(secret));
+        // A corrupted frame fails the CRC instead of returning junk.
+        let corrupted = tagged.replace(ZERO_WIDTH_ZERO, &ZERO_WIDTH_ONE.to_string());
+        assert_eq!(stego.decode_tagged(&corrupted), None);
+    }
+
+    #[test]
+    fn test_strategy_names_roundtrip() {
+        for strategy in StegoStrategy::ALL {
+            assert_eq!(StegoStrategy::from_name(strategy.name()), Some(strategy));
+        }
+        assert_eq!(StegoStrategy::from_name("carrier-pigeon"), None);
+    }
+
+    #[test]
+    fn test_json_field_roundtrip() {
+        let stego = ERdfaStego::new();
+        let json = "{\"title\":\"podcast\",\"season\":3}";
+        let carrier = stego.embed_json_field(json, "<div rel=\"eRDFa:embedded\">x</div>");
+        assert!(carrier.starts_with("{\"title\":\"podcast\",\"season\":3,"));
+        assert_eq!(
+            stego.extract_json_field(&carrier).as_deref(),
+            Some("<div rel=\"eRDFa:embedded\">x</div>")
+        );
+        // Empty object carrier via the strategy dispatch.
+        let encoded = stego.encode("payload", StegoStrategy::JsonField);
+        assert_eq!(encoded, "{\"_erdfa\":\"payload\"}");
+        assert_eq!(stego.decode(&encoded, StegoStrategy::JsonField).as_deref(), Some("payload"));
+    }
+
+    #[test]
+    fn test_json_field_roundtrip_with_backslash_n_payload() {
+        let stego = ERdfaStego::new();
+        // A literal backslash followed by `n` must not come back as a
+        // newline, and real newlines must survive alongside it.
+        for payload in ["C:\\names", "\\n", "line\nbreak \\\\n \\\""] {
+            let encoded = stego.encode(payload, StegoStrategy::JsonField);
+            assert_eq!(
+                stego.decode(&encoded, StegoStrategy::JsonField).as_deref(),
+                Some(payload)
+            );
+        }
+    }
+
+    #[test]
+    fn test_detect_strategy_markers() {
+        let stego = ERdfaStego::new();
+        let cases = [
+            StegoStrategy::HtmlEscape,
+            StegoStrategy::ZeroWidth,
+            StegoStrategy::Whitespace,
+            StegoStrategy::CommentEmbed,
+            StegoStrategy::DataAttribute,
+            StegoStrategy::HiddenDiv,
+            StegoStrategy::CssProperty,
+        ];
+        for strategy in cases {
+            let encoded = stego.encode("<p>x</p>", strategy);
+            assert_eq!(stego.detect_strategy(&encoded), Some(strategy));
+        }
+        assert_eq!(stego.detect_strategy("just ordinary text"), None);
+    }
+
+    #[test]
+    fn test_decode_auto_uses_mime_hint() {
+        let stego = ERdfaStego::new();
+        // An SVG hint routes straight to the QR decoder.
+        let qr = stego.encode("erdfa payload", StegoStrategy::QrCode);
+        assert_eq!(
+            stego.decode_auto(&qr, Some("image/svg+xml")),
+            Some((StegoStrategy::QrCode, "erdfa payload".to_string()))
+        );
+        // The same hint rejects a blob with no SVG carrier at all.
+        let comment = stego.encode("erdfa payload", StegoStrategy::CommentEmbed);
+        assert_eq!(stego.decode_auto(&comment, Some("image/svg+xml")), None);
+        // Without a hint, marker detection picks the right strategy.
+        assert_eq!(
+            stego.decode_auto(&comment, None),
+            Some((StegoStrategy::CommentEmbed, "erdfa payload".to_string()))
+        );
+        // An HTML hint narrows to markup strategies.
+        let attribute = stego.encode("erdfa payload", StegoStrategy::DataAttribute);
+        assert_eq!(
+            stego.decode_auto(&attribute, Some("text/html")),
+            Some((StegoStrategy::DataAttribute, "erdfa payload".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_qr_comment_roundtrip() {
+        let stego = ERdfaStego::new();
+        let encoded = stego.encode("erdfa payload", StegoStrategy::QrCode);
+        assert!(encoded.starts_with("data:image/svg+xml,"));
+        assert_eq!(
+            stego.decode(&encoded, StegoStrategy::QrCode).as_deref(),
+            Some("erdfa payload")
+        );
+    }
+
+    #[test]
+    fn test_svg_path_roundtrip() {
+        let encoded = visual::encode_svg_path(b"eRDFa");
+        // The carrier is real geometry, not a comment.
+        assert!(encoded.contains("<path d=\"M 0 0 l"));
+        assert!(!encoded.contains("<!--"));
+        assert_eq!(visual::decode_svg_path(&encoded).as_deref(), Some(b"eRDFa".as_slice()));
+        // An ordinary path that doesn't follow the nibble scheme is
+        // rejected rather than misread.
+        assert_eq!(visual::decode_svg_path("<path d=\"M 0 0 l 99 3\"/>"), None);
+        assert_eq!(visual::decode_svg_path("<svg></svg>"), None);
+    }
+
+    #[test]
+    fn test_visual_stego_roundtrip() {
+        // Four bytes leave two padding zeros in the final color chunk;
+        // the parser must not return them.
+        let html = visual::generate_visual_stego("eRDF");
+        assert_eq!(visual::parse_visual_stego(&html).as_deref(), Some("eRDF"));
+    }
+
+    #[test]
+    fn test_position_roundtrips_every_byte() {
+        for b in 0..=u8::MAX {
+            let encoded = visual::encode_position(&[b]);
+            assert_eq!(visual::decode_position(&encoded), vec![b]);
+        }
+    }
+
+    #[test]
+    fn test_bitmap_lsb_rejects_small_carrier() {
+        let mut carrier = vec![0u8; 15];
+        assert_eq!(
+            visual::encode_bitmap_lsb(&mut carrier, b"AB"),
+            Err(StegoError::CarrierTooSmall {
+                needed: 16,
+                available: 15
+            })
+        );
+        // The undersized carrier was left untouched.
+        assert!(carrier.iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_bitmap_lsb_decode_out_of_range() {
+        let mut carrier = vec![0u8; 16];
+        visual::encode_bitmap_lsb(&mut carrier, b"AB").unwrap();
+        assert_eq!(visual::decode_bitmap_lsb(&carrier, 2).as_deref(), Some(b"AB".as_slice()));
+        assert_eq!(visual::decode_bitmap_lsb(&carrier, 3), None);
+    }
+
+    #[test]
+    fn test_capacity_bitmap_and_color() {
+        let stego = ERdfaStego::new();
+        assert_eq!(stego.capacity(StegoStrategy::Bitmap, 64), 8);
+        assert_eq!(stego.capacity(StegoStrategy::Bitmap, 7), 0);
+        assert_eq!(stego.capacity(StegoStrategy::Color, 4), 12);
+        assert_eq!(stego.capacity(StegoStrategy::ZeroWidth, 0), usize::MAX);
+    }
+
+    #[test]
+    fn test_every_strategy_has_a_hostility_rating() {
+        let stego = ERdfaStego::new();
+        assert_eq!(stego.max_hostility(StegoStrategy::Bitmap), HostilityLevel::MaximumHostile);
+        assert_eq!(stego.max_hostility(StegoStrategy::VisualNoise), HostilityLevel::MaximumHostile);
+        assert_eq!(stego.max_hostility(StegoStrategy::Color), HostilityLevel::Paranoid);
+        assert_eq!(stego.max_hostility(StegoStrategy::CssProperty), HostilityLevel::Paranoid);
+        for strategy in StegoStrategy::ALL {
+            // Every strategy rates at least Friendly.
+            assert!(stego.max_hostility(strategy) >= HostilityLevel::Friendly);
+        }
+        for level in [
+            HostilityLevel::Friendly,
+            HostilityLevel::Normal,
+            HostilityLevel::Hostile,
+            HostilityLevel::MaximumHostile,
+            HostilityLevel::Paranoid,
+        ] {
+            let chosen = stego.select_strategy(level);
+            assert!(stego.max_hostility(chosen) >= level);
+        }
+    }
+
+    #[test]
+    fn test_select_strategy_defaults_to_multi_layer_beyond_named_tiers() {
+        let stego = ERdfaStego::default();
+        assert_eq!(
+            stego.select_strategy(HostilityLevel::MaximumHostile),
+            StegoStrategy::MultiLayer
+        );
+        // A tier beyond the named ones picks the most robust strategy
+        // rather than panicking.
+        assert!(HostilityLevel::Custom(9) > HostilityLevel::Paranoid);
+        assert_eq!(
+            stego.select_strategy(HostilityLevel::Custom(9)),
+            StegoStrategy::MultiLayer
+        );
+    }
+
+    #[test]
+    fn test_unicode_roundtrip_ascii() {
+        let stego = ERdfaStego::new();
+        let original = "Escaped RDFa: Take Any CONTENT home";
+        let encoded = stego.encode(original, StegoStrategy::Unicode);
+        assert_ne!(encoded, original);
+        assert_eq!(stego.decode(&encoded, StegoStrategy::Unicode).as_deref(), Some(original));
+    }
+
+    #[test]
+    fn test_unicode_preserves_native_cyrillic() {
+        let stego = ERdfaStego::new();
+        let original = "смешанный text";
+        let encoded = stego.encode(original, StegoStrategy::Unicode);
+        assert_eq!(stego.decode(&encoded, StegoStrategy::Unicode).as_deref(), Some(original));
+    }
+
+    #[test]
+    fn test_comment_roundtrip() {
+        let stego = ERdfaStego::new();
+        let encoded = stego.encode("<div rel=\"eRDFa:embedded\">", StegoStrategy::CommentEmbed);
+        assert_eq!(
+            stego.decode(&encoded, StegoStrategy::CommentEmbed).as_deref(),
+            Some("<div rel=\"eRDFa:embedded\">")
+        );
+    }
+
+    #[test]
+    fn test_marker_decoders_tolerate_surrounding_markup() {
+        let stego = ERdfaStego::new();
+        for strategy in [
+            StegoStrategy::CommentEmbed,
+            StegoStrategy::DataAttribute,
+            StegoStrategy::HiddenDiv,
+            StegoStrategy::CssProperty,
+            StegoStrategy::JsonField,
+            StegoStrategy::QrCode,
+        ] {
+            let encoded = stego.encode("wrapped payload", strategy);
+            let hosted = format!("<p>platform chrome {}</p>\ntrailing boilerplate", encoded);
+            assert_eq!(
+                stego.decode(&hosted, strategy).as_deref(),
+                Some("wrapped payload"),
+                "{:?}",
+                strategy
+            );
+            // The marker must still be genuinely required.
+            assert_eq!(stego.decode("<p>no marker here</p>", strategy), None, "{:?}", strategy);
+        }
+    }
+
+    #[test]
+    fn test_multi_layer_roundtrip() {
+        let stego = ERdfaStego::new();
+        let encoded = stego.encode("<p>secret</p>", StegoStrategy::MultiLayer);
+        assert_eq!(
+            stego.decode(&encoded, StegoStrategy::MultiLayer).as_deref(),
+            Some("<p>secret</p>")
+        );
+    }
+
+    #[test]
+    fn test_encode_layers_roundtrip_and_guards() {
+        let stego = ERdfaStego::new();
+        let layers = [StegoStrategy::HtmlEscape, StegoStrategy::CommentEmbed];
+        let encoded = stego.encode_layers("<p>secret</p>", &layers).unwrap();
+        assert_eq!(
+            stego.decode_layers(&encoded, &layers).as_deref(),
+            Some("<p>secret</p>")
+        );
+        assert_eq!(stego.encode_layers("x", &[]), Err(StegoError::EmptyLayerSpec));
+        assert_eq!(
+            stego.encode_layers("x", &[StegoStrategy::MultiLayer]),
+            Err(StegoError::RecursiveLayerSpec)
+        );
+        assert_eq!(stego.decode_layers("x", &[]), None);
+    }
+
+    #[test]
+    fn test_multi_layer_custom_spec_roundtrip() {
+        let stego = ERdfaStego::with_multi_layer_spec(vec![
+            StegoStrategy::HtmlEscape,
+            StegoStrategy::ZeroWidth,
+            StegoStrategy::CommentEmbed,
+        ]);
+        let encoded = stego.encode("<p>secret</p>", StegoStrategy::MultiLayer);
+        assert!(encoded.starts_with("<!--erdfa:"));
+        assert_eq!(
+            stego.decode(&encoded, StegoStrategy::MultiLayer).as_deref(),
+            Some("<p>secret</p>")
+        );
+    }
+}
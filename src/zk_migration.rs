@@ -0,0 +1,141 @@
+//! Tracking the migration of document shards into block 2 of the
+//! zero-knowledge chain.
+//!
+//! Every shard a holder migrates is recorded and appended to the
+//! serialized block-2 payload, so the full migration can be replayed
+//! and audited.
+
+use std::sync::{Arc, Mutex};
+
+/// One shard's migration event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationRecord {
+    pub shard_id: usize,
+    pub holder_address: Vec<u8>,
+    pub migrated_at: u64,
+}
+
+impl MigrationRecord {
+    fn serialize_into(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(&(self.shard_id as u64).to_be_bytes());
+        out.extend_from_slice(&self.migrated_at.to_be_bytes());
+        out.extend_from_slice(&(self.holder_address.len() as u16).to_be_bytes());
+        out.extend_from_slice(&self.holder_address);
+    }
+}
+
+/// Records shard migrations and maintains the serialized block-2
+/// payload incrementally — each migration appends its own bytes rather
+/// than re-serializing the whole history.
+pub struct MigrationTracker {
+    pub migrations: Vec<MigrationRecord>,
+    pub block_2_data: Vec<u8>,
+}
+
+impl MigrationTracker {
+    pub fn new() -> Self {
+        MigrationTracker {
+            migrations: Vec::new(),
+            block_2_data: Vec::new(),
+        }
+    }
+
+    pub fn track_migration(&mut self, shard_id: usize, holder_address: Vec<u8>, migrated_at: u64) {
+        let record = MigrationRecord {
+            shard_id,
+            holder_address,
+            migrated_at,
+        };
+        record.serialize_into(&mut self.block_2_data);
+        self.migrations.push(record);
+    }
+
+    pub fn migration_count(&self) -> usize {
+        self.migrations.len()
+    }
+}
+
+impl Default for MigrationTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Thread-safe handle over a shared [`MigrationTracker`], for when
+/// several migrators submit concurrently.
+#[derive(Clone)]
+pub struct SharedMigrationTracker {
+    inner: Arc<Mutex<MigrationTracker>>,
+}
+
+impl SharedMigrationTracker {
+    pub fn new() -> Self {
+        SharedMigrationTracker {
+            inner: Arc::new(Mutex::new(MigrationTracker::new())),
+        }
+    }
+
+    pub fn track_migration(&self, shard_id: usize, holder_address: Vec<u8>, migrated_at: u64) {
+        self.inner
+            .lock()
+            .expect("migration tracker lock poisoned")
+            .track_migration(shard_id, holder_address, migrated_at);
+    }
+
+    pub fn migration_count(&self) -> usize {
+        self.inner
+            .lock()
+            .expect("migration tracker lock poisoned")
+            .migration_count()
+    }
+
+    /// Run a closure against the locked tracker, e.g. to snapshot
+    /// `block_2_data`.
+    pub fn with<R>(&self, f: impl FnOnce(&MigrationTracker) -> R) -> R {
+        f(&self.inner.lock().expect("migration tracker lock poisoned"))
+    }
+}
+
+impl Default for SharedMigrationTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_block_2_data_appends_incrementally() {
+        let mut tracker = MigrationTracker::new();
+        tracker.track_migration(1, b"holder-a".to_vec(), 10);
+        let after_one = tracker.block_2_data.clone();
+        tracker.track_migration(2, b"holder-b".to_vec(), 11);
+        // The first record's bytes are untouched by the second append.
+        assert_eq!(&tracker.block_2_data[..after_one.len()], &after_one[..]);
+        assert!(tracker.block_2_data.len() > after_one.len());
+    }
+
+    #[test]
+    fn test_concurrent_migrations_all_land() {
+        let tracker = SharedMigrationTracker::new();
+        let mut handles = Vec::new();
+        for shard_id in 0..71 {
+            let tracker = tracker.clone();
+            handles.push(thread::spawn(move || {
+                tracker.track_migration(shard_id, format!("holder-{}", shard_id).into_bytes(), 42);
+            }));
+        }
+        for handle in handles {
+            handle.join().expect("migrator thread panicked");
+        }
+        assert_eq!(tracker.migration_count(), 71);
+        tracker.with(|inner| {
+            let mut ids: Vec<usize> = inner.migrations.iter().map(|m| m.shard_id).collect();
+            ids.sort_unstable();
+            assert_eq!(ids, (0..71).collect::<Vec<_>>());
+        });
+    }
+}